@@ -0,0 +1,336 @@
+use super::dump::Dump;
+#[cfg(feature = "bytes")]
+use bytes::{buf::UninitSlice, BufMut, Bytes};
+use std::{
+    fmt,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+/// Smallest and largest size classes, as powers of two.
+const MIN_CLASS: u32 = 6;
+const MAX_CLASS: u32 = 20;
+
+/// Default for [with_retained_bytes](crate::BufferPool::with_retained_bytes).
+const DEFAULT_RETAINED_BYTES: usize = 4 << 20;
+
+/// A pool of byte buffers of varying sizes.
+///
+/// Buffers are kept in size classes of powers of two from 64 bytes
+/// to [MAX_CAPACITY](crate::BufferPool::MAX_CAPACITY), each class being
+/// a lock free dump like the one inside a [FreeList](crate::FreeList).
+/// [get](crate::BufferPool::get) hands out an empty `Vec<u8>` with at least
+/// the requested capacity wrapped in a [PooledBuf], which puts it back
+/// into its class when dropped.
+///
+/// A buffer is only kept if its capacity is still a power of two within
+/// the classes when it is returned. Growing a `Vec<u8>` past its capacity
+/// doubles it, so this usually holds even for buffers that grew.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::BufferPool;
+///
+/// let pool = BufferPool::new();
+///
+/// let mut buf = pool.get(1000);
+/// assert!(buf.capacity() >= 1000);
+///
+/// buf.extend_from_slice(b"hello");
+/// drop(buf);
+///
+/// // same allocation, emptied
+/// let buf = pool.get(1000);
+/// assert!(buf.is_empty());
+/// ```
+pub struct BufferPool {
+    /// `classes[i]` holds buffers of capacity `1 << (MIN_CLASS + i)`.
+    classes: Vec<Dump<u8>>,
+    retained_bytes: usize,
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BufferPool {
+    /// Largest capacity pooled. Bigger buffers can be requested
+    /// but they are dropped when returned.
+    pub const MAX_CAPACITY: usize = 1 << MAX_CLASS;
+
+    /// Returns an empty pool.
+    pub fn new() -> Self {
+        BufferPool {
+            classes: (MIN_CLASS..=MAX_CLASS).map(|_| Dump::new()).collect(),
+            retained_bytes: DEFAULT_RETAINED_BYTES,
+        }
+    }
+
+    /// Limits the total capacity of the buffers kept in each size class
+    /// to about `bytes`. Buffers returned while their class is at the limit
+    /// are dropped. The default is 4 MiB per class.
+    ///
+    /// A class also holds at most as many buffers as a [FreeList](crate::FreeList).
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::BufferPool;
+    ///
+    /// let pool = BufferPool::new().with_retained_bytes(8192);
+    ///
+    /// let bufs = (0..4).map(|_| pool.get(4096)).collect::<Vec<_>>();
+    /// drop(bufs);
+    ///
+    /// assert_eq!(pool.retained(), 8192);
+    /// ```
+    pub fn with_retained_bytes(mut self, bytes: usize) -> Self {
+        self.retained_bytes = bytes;
+        self
+    }
+
+    /// Returns an empty buffer with a capacity of at least `min_capacity`,
+    /// reusing one from the pool if there is any.
+    pub fn get(&self, min_capacity: usize) -> PooledBuf<&BufferPool> {
+        let buf = match Self::class_to_get(min_capacity) {
+            Some(class) => {
+                let capacity = 1 << class;
+
+                match self.classes[(class - MIN_CLASS) as usize].recycle() {
+                    Ok(ptr) => unsafe { Vec::from_raw_parts(ptr, 0, capacity) },
+                    Err(()) => Vec::with_capacity(capacity),
+                }
+            }
+            None => Vec::with_capacity(min_capacity),
+        };
+
+        PooledBuf {
+            buf: ManuallyDrop::new(buf),
+            pool: self,
+        }
+    }
+
+    /// Same as [get](crate::BufferPool::get), but the buffer holds on to the pool
+    /// instead of borrowing it, so that it can be sent to other threads or
+    /// turned into [Bytes](https://docs.rs/bytes) with the `bytes` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::BufferPool;
+    /// use std::{sync::Arc, thread};
+    ///
+    /// let pool = Arc::new(BufferPool::new());
+    ///
+    /// let mut buf = pool.get_shared(100);
+    /// buf.extend_from_slice(b"frame");
+    ///
+    /// thread::spawn(move || drop(buf)).join().unwrap();
+    /// assert_eq!(pool.retained(), 128);
+    /// ```
+    pub fn get_shared(self: &Arc<Self>, min_capacity: usize) -> SharedBuf {
+        let buf = self.get(min_capacity).into_vec();
+
+        SharedBuf {
+            buf: ManuallyDrop::new(buf),
+            pool: Arc::clone(self),
+        }
+    }
+
+    /// Returns the total capacity of the buffers in the pool.
+    ///
+    /// Only approximate if other threads are using the pool.
+    pub fn retained(&self) -> usize {
+        self.classes
+            .iter()
+            .zip(MIN_CLASS..)
+            .map(|(dump, class)| dump.len() << class)
+            .sum()
+    }
+
+    /// Class of the buffers handed out for `min_capacity`,
+    /// or None if it is too big to be pooled.
+    fn class_to_get(min_capacity: usize) -> Option<u32> {
+        let class = min_capacity
+            .max(1 << MIN_CLASS)
+            .checked_next_power_of_two()?
+            .trailing_zeros();
+
+        if class <= MAX_CLASS {
+            Some(class)
+        } else {
+            None
+        }
+    }
+
+    /// Keeps `buf` in its size class if it fits one and the class
+    /// isn't at its limit, otherwise drops it.
+    fn put(&self, mut buf: Vec<u8>) {
+        let capacity = buf.capacity();
+
+        if !capacity.is_power_of_two() {
+            return;
+        }
+
+        let class = capacity.trailing_zeros();
+
+        if !(MIN_CLASS..=MAX_CLASS).contains(&class) {
+            return;
+        }
+
+        let dump = &self.classes[(class - MIN_CLASS) as usize];
+
+        if (dump.len() + 1) * capacity > self.retained_bytes {
+            return;
+        }
+
+        buf.clear();
+
+        let ptr = ManuallyDrop::new(buf).as_mut_ptr();
+
+        if dump.throw(ptr).is_err() {
+            drop(unsafe { Vec::from_raw_parts(ptr, 0, capacity) });
+        }
+    }
+}
+
+/// Drops all the buffers in the pool.
+impl Drop for BufferPool {
+    fn drop(&mut self) {
+        for (dump, class) in self.classes.iter().zip(MIN_CLASS..) {
+            unsafe {
+                dump.for_each(|ptr| drop(Vec::from_raw_parts(ptr, 0, 1 << class)));
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("retained", &self.retained())
+            .field("retained_bytes", &self.retained_bytes)
+            .finish()
+    }
+}
+
+/// A `Vec<u8>` from a [BufferPool], put back into the pool when dropped.
+///
+/// `P` is how the buffer gets to its pool: `&BufferPool` for buffers from
+/// [get](crate::BufferPool::get) and `Arc<BufferPool>` for those from
+/// [get_shared](crate::BufferPool::get_shared), see [SharedBuf].
+///
+/// It implements Deref and DerefMut to the `Vec<u8>`, and `BufMut`
+/// with the `bytes` feature. It stands in for `BytesMut`, which this crate
+/// doesn't hand out since a `BytesMut` can't be put back into the pool
+/// when it is dropped.
+pub struct PooledBuf<P: Deref<Target = BufferPool>> {
+    buf: ManuallyDrop<Vec<u8>>,
+    pool: P,
+}
+
+/// A [PooledBuf] from [get_shared](crate::BufferPool::get_shared), which keeps
+/// the pool alive through an [Arc] instead of borrowing it.
+pub type SharedBuf = PooledBuf<Arc<BufferPool>>;
+
+impl<P: Deref<Target = BufferPool>> PooledBuf<P> {
+    /// Takes the buffer out, it won't be returned to the pool.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        // The empty `Vec` left behind isn't kept by the pool
+        std::mem::take(&mut *self.buf)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<P: Deref<Target = BufferPool> + Send + 'static> PooledBuf<P> {
+    /// Turns the buffer into [Bytes] without copying.
+    /// It is put back into the pool when the last clone of the `Bytes` is dropped.
+    ///
+    /// `Bytes` can't borrow, so the pool has to be shared through an [Arc]
+    /// with [get_shared](crate::BufferPool::get_shared) or be `'static`,
+    /// e.g. a pool in a static or one that is leaked.
+    ///
+    /// # Example
+    /// ```
+    /// use bytes::BufMut;
+    /// use lock_free_freelist::BufferPool;
+    /// use std::sync::Arc;
+    ///
+    /// let pool = Arc::new(BufferPool::new());
+    ///
+    /// let mut buf = pool.get_shared(100);
+    /// buf.put_u32(42);
+    /// buf.put_slice(b"payload");
+    ///
+    /// let frame = buf.freeze();
+    /// let clone = frame.clone();
+    /// drop(frame);
+    /// assert_eq!(pool.retained(), 0);
+    ///
+    /// drop(clone);
+    /// assert_eq!(pool.retained(), 128);
+    /// ```
+    pub fn freeze(self) -> Bytes {
+        Bytes::from_owner(self)
+    }
+}
+
+impl<P: Deref<Target = BufferPool>> Deref for PooledBuf<P> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+impl<P: Deref<Target = BufferPool>> DerefMut for PooledBuf<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buf
+    }
+}
+
+impl<P: Deref<Target = BufferPool>> AsRef<[u8]> for PooledBuf<P> {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl<P: Deref<Target = BufferPool>> AsMut<[u8]> for PooledBuf<P> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+impl<P: Deref<Target = BufferPool>> fmt::Debug for PooledBuf<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.buf, f)
+    }
+}
+
+#[cfg(feature = "bytes")]
+unsafe impl<P: Deref<Target = BufferPool>> BufMut for PooledBuf<P> {
+    fn remaining_mut(&self) -> usize {
+        self.buf.remaining_mut()
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.buf.advance_mut(cnt)
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.buf.chunk_mut()
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        self.buf.put_slice(src)
+    }
+}
+
+impl<P: Deref<Target = BufferPool>> Drop for PooledBuf<P> {
+    fn drop(&mut self) {
+        let buf = unsafe { ManuallyDrop::take(&mut self.buf) };
+        self.pool.put(buf);
+    }
+}
@@ -0,0 +1,237 @@
+use super::dump::Dump;
+use std::{
+    fmt,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for String {}
+    impl<T> Sealed for Vec<T> {}
+}
+
+/// A growable collection whose allocation a [CollectionPool] can keep
+/// for reuse. Implemented for `String` and `Vec<T>`.
+pub trait Collection: Default + sealed::Sealed {
+    /// Removes the contents, keeping the allocation.
+    fn clear(&mut self);
+
+    /// Number of elements it can hold without reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Makes room for at least `additional` more elements.
+    fn reserve(&mut self, additional: usize);
+}
+
+impl Collection for String {
+    fn clear(&mut self) {
+        String::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        String::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        String::reserve(self, additional)
+    }
+}
+
+impl<T> Collection for Vec<T> {
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional)
+    }
+}
+
+/// A pool of `String`s.
+pub type StringPool = CollectionPool<String>;
+
+/// A pool of `Vec<T>`s.
+pub type VecPool<T> = CollectionPool<Vec<T>>;
+
+/// A pool of collections that keeps their allocations.
+///
+/// A `FreeList<Box<String>>` reuses the box holding the `String`,
+/// but [set_new_val](crate::Reusable::set_new_val) replaces the `String`
+/// and with it the buffer. This pool instead hands out the collections
+/// themselves, emptied but with their capacity, and takes them back when
+/// the [Pooled] wrapping them is dropped. Use it through [StringPool]
+/// and [VecPool].
+///
+/// Like a [FreeList](crate::FreeList), it holds as many collections as
+/// there are bits in `usize` and drops the ones returned when it is full.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::StringPool;
+///
+/// let pool = StringPool::new();
+///
+/// let mut line = pool.get();
+/// line.push_str("GET / HTTP/1.1");
+/// let capacity = line.capacity();
+/// drop(line);
+///
+/// let line = pool.get();
+/// assert!(line.is_empty());
+/// assert_eq!(line.capacity(), capacity);
+/// ```
+pub struct CollectionPool<C: Collection> {
+    dump: Dump<C>,
+    max_capacity: usize,
+}
+
+impl<C: Collection> Default for CollectionPool<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Collection> CollectionPool<C> {
+    /// Returns an empty pool.
+    pub fn new() -> Self {
+        CollectionPool {
+            dump: Dump::new(),
+            max_capacity: usize::MAX,
+        }
+    }
+
+    /// Drops returned collections with a capacity above `capacity`
+    /// instead of keeping them, so that one huge collection
+    /// doesn't stay in the pool forever.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::VecPool;
+    ///
+    /// let pool = VecPool::<u32>::new().with_max_capacity(1024);
+    ///
+    /// let mut big = pool.get();
+    /// big.resize(1_000_000, 0);
+    /// drop(big);
+    ///
+    /// assert_eq!(pool.len(), 0);
+    /// ```
+    pub fn with_max_capacity(mut self, capacity: usize) -> Self {
+        self.max_capacity = capacity;
+        self
+    }
+
+    /// Returns an empty collection, reusing one from the pool if there is any.
+    pub fn get(&self) -> Pooled<'_, C> {
+        let collection = match self.dump.recycle() {
+            Ok(ptr) => unsafe { Box::from_raw(ptr) },
+            Err(()) => Box::default(),
+        };
+
+        Pooled {
+            collection: ManuallyDrop::new(collection),
+            pool: self,
+        }
+    }
+
+    /// Like [get](crate::CollectionPool::get) but makes sure
+    /// the collection has room for at least `capacity` elements.
+    pub fn get_with_capacity(&self, capacity: usize) -> Pooled<'_, C> {
+        let mut pooled = self.get();
+        pooled.reserve(capacity);
+        pooled
+    }
+
+    /// Returns the number of collections in the pool.
+    ///
+    /// Only approximate if other threads are using the pool.
+    pub fn len(&self) -> usize {
+        self.dump.len()
+    }
+
+    /// Returns true if there are no collections in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn put(&self, mut collection: Box<C>) {
+        if collection.capacity() > self.max_capacity {
+            return;
+        }
+
+        collection.clear();
+
+        if let Err(ptr) = self.dump.throw(Box::into_raw(collection)) {
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
+}
+
+/// Drops all the collections in the pool.
+impl<C: Collection> Drop for CollectionPool<C> {
+    fn drop(&mut self) {
+        unsafe {
+            self.dump.for_each(|ptr| drop(Box::from_raw(ptr)));
+        }
+    }
+}
+
+impl<C: Collection> fmt::Debug for CollectionPool<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CollectionPool")
+            .field("len", &self.len())
+            .field("max_capacity", &self.max_capacity)
+            .finish()
+    }
+}
+
+/// A collection from a [CollectionPool], put back into the pool when dropped.
+///
+/// It implements Deref and DerefMut to the collection.
+pub struct Pooled<'a, C: Collection> {
+    collection: ManuallyDrop<Box<C>>,
+    pool: &'a CollectionPool<C>,
+}
+
+impl<'a, C: Collection> Pooled<'a, C> {
+    /// Takes the collection out, it won't be returned to the pool.
+    pub fn into_inner(mut self) -> C {
+        let collection = unsafe { ManuallyDrop::take(&mut self.collection) };
+        std::mem::forget(self);
+        *collection
+    }
+}
+
+impl<'a, C: Collection> Deref for Pooled<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.collection
+    }
+}
+
+impl<'a, C: Collection> DerefMut for Pooled<'a, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.collection
+    }
+}
+
+impl<'a, C: Collection + fmt::Debug> fmt::Debug for Pooled<'a, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self.collection, f)
+    }
+}
+
+impl<'a, C: Collection> Drop for Pooled<'a, C> {
+    fn drop(&mut self) {
+        let collection = unsafe { ManuallyDrop::take(&mut self.collection) };
+        self.pool.put(collection);
+    }
+}
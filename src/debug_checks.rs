@@ -0,0 +1,42 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, MutexGuard},
+};
+
+/// Addresses of pointers, for the checks of the `debug-checks` feature.
+pub(crate) struct AddressSet {
+    addresses: Mutex<BTreeSet<usize>>,
+}
+
+impl AddressSet {
+    pub const fn new() -> Self {
+        AddressSet {
+            addresses: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeSet<usize>> {
+        // A failed check panics while other threads may hold the lock,
+        // the set is still usable by the destructors that run afterwards.
+        self.addresses
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns false if `ptr` was already in the set.
+    pub fn insert<T>(&self, ptr: *const T) -> bool {
+        self.lock().insert(ptr as usize)
+    }
+
+    pub fn remove<T>(&self, ptr: *const T) {
+        self.lock().remove(&(ptr as usize));
+    }
+
+    pub fn contains<T>(&self, ptr: *const T) -> bool {
+        self.lock().contains(&(ptr as usize))
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
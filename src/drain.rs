@@ -0,0 +1,42 @@
+use super::{free_list::FreeList, reusable::Reusable, smart_pointer::SmartPointer};
+use std::{ops::Deref, sync::atomic::Ordering};
+
+/// Iterator returned by [FreeList::drain](crate::FreeList::drain).
+///
+/// It takes the free pointers out of the free list one by one
+/// until it is empty and yields them as smart pointers.
+/// Pointers not taken when the iterator is dropped stay in the free list.
+pub struct Drain<'a, T: SmartPointer>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    free_list: &'a FreeList<T>,
+}
+
+impl<'a, T: SmartPointer> Drain<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    pub(crate) fn new(free_list: &'a FreeList<T>) -> Self {
+        Drain { free_list }
+    }
+}
+
+impl<'a, T: SmartPointer> Iterator for Drain<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let ptr = self.free_list.take()?;
+
+        // The pointer doesn't belong to the free list anymore
+        self.free_list.live.fetch_sub(1, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        self.free_list.tags.remove(ptr);
+        self.free_list.waiters.notify_fenced();
+
+        Some(unsafe { T::from_raw(ptr) })
+    }
+}
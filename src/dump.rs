@@ -1,8 +1,13 @@
+#[cfg(feature = "debug-checks")]
+use super::debug_checks::AddressSet;
+#[cfg(feature = "stats")]
+use super::stats::Counters;
 use bit_fiddler::{max_bits, set, unset};
 use std::{
     cell::UnsafeCell,
+    fmt,
     ptr::null_mut,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
 };
 
 /// In this struct,
@@ -10,15 +15,58 @@ use std::{
 ///
 /// The accesses to dump[] array are synchronized by reader_bitmap
 /// and writer_bitmap.
+///
+/// `stamps[i]` is the time `recycle_idle()` first saw `dump[i]`,
+/// or 0 if it hasn't seen it since it was thrown.
+///
+/// With the `debug-checks` feature, `thrown` holds the values in `dump[]`
+/// so that `throw()` can catch a value being thrown twice. It is None
+/// for dumps made by `new_unchecked()`.
 pub struct Dump<T> {
     reader_bitmap: AtomicUsize,
     writer_bitmap: AtomicUsize,
     dump: UnsafeCell<[*mut T; max_bits!(type = usize)]>,
+    stamps: [AtomicU64; max_bits!(type = usize)],
+    #[cfg(feature = "stats")]
+    pub(crate) counters: Counters,
+    #[cfg(feature = "debug-checks")]
+    thrown: Option<AddressSet>,
 }
 
+#[allow(clippy::declare_interior_mutable_const)]
+const UNSEEN: AtomicU64 = AtomicU64::new(0);
+
 unsafe impl<T> Send for Dump<T> {}
 unsafe impl<T> Sync for Dump<T> {}
 
+/// Shows the bitmaps in binary, least significant bit being index 0 of `dump[]`.
+/// Bits set in `writer_bitmap` but not in `reader_bitmap` are
+/// spots being written to or read from at the moment.
+impl<T> fmt::Debug for Dump<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = max_bits!(type = usize) + 2;
+
+        f.debug_struct("Dump")
+            .field(
+                "reader_bitmap",
+                &format_args!(
+                    "{:#0width$b}",
+                    self.reader_bitmap.load(Ordering::Relaxed),
+                    width = width
+                ),
+            )
+            .field(
+                "writer_bitmap",
+                &format_args!(
+                    "{:#0width$b}",
+                    self.writer_bitmap.load(Ordering::Relaxed),
+                    width = width
+                ),
+            )
+            .finish()
+    }
+}
+
 impl<T> Dump<T> {
     /// Returns a new Dump instance.
     ///
@@ -31,11 +79,32 @@ impl<T> Dump<T> {
     ///
     /// let dump = Dump::<Example>::new();
     /// ```
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
+        Self::with_checks(true)
+    }
+
+    /// Like `new()` but without the checks of the `debug-checks` feature.
+    ///
+    /// Those checks allocate, this is for dumps used by an allocator.
+    pub const fn new_unchecked() -> Self {
+        Self::with_checks(false)
+    }
+
+    #[cfg_attr(not(feature = "debug-checks"), allow(unused_variables))]
+    const fn with_checks(checked: bool) -> Self {
         Dump {
             reader_bitmap: AtomicUsize::new(0),
             writer_bitmap: AtomicUsize::new(0),
             dump: UnsafeCell::new([null_mut::<T>(); max_bits!(type = usize)]),
+            stamps: [UNSEEN; max_bits!(type = usize)],
+            #[cfg(feature = "stats")]
+            counters: Counters::new(),
+            #[cfg(feature = "debug-checks")]
+            thrown: if checked {
+                Some(AddressSet::new())
+            } else {
+                None
+            },
         }
     }
 
@@ -54,7 +123,19 @@ impl<T> Dump<T> {
     /// 5) After storing `raw` in the `dump[]`, we tell reader threads
     ///    that this index is available for read. To do this, we set this
     ///    same bit position in `reader_bitmap` atomically.
+    ///
+    /// With the `debug-checks` feature, it panics if `raw` is already in the dump.
     pub fn throw(&self, raw: *mut T) -> Result<(), *mut T> {
+        #[cfg(feature = "debug-checks")]
+        if let Some(thrown) = &self.thrown {
+            assert!(
+                thrown.insert(raw),
+                "pointer {:p} thrown into the free list while already in it, \
+                 it would be handed out twice",
+                raw
+            );
+        }
+
         let mut old_writer_bitmap = self.writer_bitmap.load(Ordering::Relaxed);
         let mut first_empty_spot;
 
@@ -64,6 +145,11 @@ impl<T> Dump<T> {
 
             // occupy `first_empty_spot` in `old_writer_bitmap` and assign it to `new_writer_bitmap`
             let new_writer_bitmap = if first_empty_spot as usize == max_bits!(type = usize) {
+                #[cfg(feature = "debug-checks")]
+                if let Some(thrown) = &self.thrown {
+                    thrown.remove(raw);
+                }
+
                 return Err(raw);
             } else {
                 set!(old_writer_bitmap, usize, first_empty_spot)
@@ -76,7 +162,10 @@ impl<T> Dump<T> {
                 Ordering::Relaxed,
             ) {
                 Ok(_) => break,
-                Err(old) => old_writer_bitmap = old,
+                Err(old) => {
+                    record_stat!(self, cas_retries);
+                    old_writer_bitmap = old
+                }
             };
         }
 
@@ -86,28 +175,44 @@ impl<T> Dump<T> {
             (*dump_ptr)[first_empty_spot as usize] = raw;
         }
 
+        self.stamps[first_empty_spot as usize].store(0, Ordering::Relaxed);
+
         let mut old_reader_bitmap = self.reader_bitmap.load(Ordering::Relaxed);
 
         loop {
             let new_reader_bitmap = set!(old_reader_bitmap, usize, first_empty_spot);
 
             /*
-             * Memory order on success should be `Ordering::Release`.
+             * Memory order on success should be at least `Ordering::Release`.
              * If it was Ordering::Relaxed, it would become possible
              * that `recycle()` sees this bit as set in `reader_bitmap`
              * but doesn't see the newly updated value in `dump[]`.
+             *
+             * It is `Ordering::SeqCst` so that the free list can wake up
+             * waiters without a fence, see `Waiters::notify()`.
+             * A successful CAS costs the same either way on x86.
              */
             match self.reader_bitmap.compare_exchange_weak(
                 old_reader_bitmap,
                 new_reader_bitmap,
-                Ordering::Release,
+                Ordering::SeqCst,
                 Ordering::Relaxed,
             ) {
-                Ok(_) => break,
-                Err(old) => old_reader_bitmap = old,
+                Ok(_) => {
+                    #[cfg(feature = "stats")]
+                    self.counters
+                        .record_len(new_reader_bitmap.count_ones() as usize);
+                    break;
+                }
+                Err(old) => {
+                    record_stat!(self, cas_retries);
+                    old_reader_bitmap = old
+                }
             };
         }
 
+        record_stat!(self, throws);
+
         Ok(())
     }
 
@@ -147,7 +252,10 @@ impl<T> Dump<T> {
                 Ordering::Relaxed,
             ) {
                 Ok(_) => break,
-                Err(old) => old_reader_bitmap = old,
+                Err(old) => {
+                    record_stat!(self, cas_retries);
+                    old_reader_bitmap = old
+                }
             };
         }
 
@@ -155,6 +263,11 @@ impl<T> Dump<T> {
 
         let retval = unsafe { (*dump_ptr)[first_set_spot as usize] };
 
+        #[cfg(feature = "debug-checks")]
+        if let Some(thrown) = &self.thrown {
+            thrown.remove(retval);
+        }
+
         let mut old_writer_bitmap = self.writer_bitmap.load(Ordering::Relaxed);
 
         loop {
@@ -167,26 +280,120 @@ impl<T> Dump<T> {
                 Ordering::Relaxed,
             ) {
                 Ok(_) => break,
-                Err(old) => old_writer_bitmap = old,
+                Err(old) => {
+                    record_stat!(self, cas_retries);
+                    old_writer_bitmap = old
+                }
             };
         }
 
         Ok(retval)
     }
 
+    /// Calls `f` with every value that has been in the dump for longer than `max_age`
+    /// and removes it from the dump. `now` and `max_age` are in the same unit
+    /// as the timestamps passed to earlier calls, `now` must not be 0.
+    ///
+    /// A value is stamped with `now` by the first call that sees it,
+    /// so it is only removed by a later call. This keeps `throw()` from
+    /// having to read the clock, at the cost of keeping values for up to
+    /// one extra interval between calls.
+    ///
+    /// This is thread safe, values are taken out in the same way
+    /// as `recycle()` but only from the indices with old enough stamps.
+    pub fn recycle_idle<F>(&self, now: u64, max_age: u64, mut f: F)
+    where
+        F: FnMut(*mut T),
+    {
+        let is_idle = |seen: u64| seen != 0 && now.saturating_sub(seen) > max_age;
+
+        let mut candidates = self.reader_bitmap.load(Ordering::Acquire);
+
+        loop {
+            let spot = candidates.trailing_zeros();
+
+            if spot as usize == max_bits!(type = usize) {
+                break;
+            }
+
+            unset!(in candidates, usize, spot);
+
+            let stamp = &self.stamps[spot as usize];
+            let seen = stamp.load(Ordering::Relaxed);
+
+            if seen == 0 {
+                let _ = stamp.compare_exchange(0, now, Ordering::Relaxed, Ordering::Relaxed);
+                continue;
+            } else if !is_idle(seen) {
+                continue;
+            }
+
+            // Take the spot for ourselves, just like `recycle()` does.
+            let spot_bit = set!(0, usize, spot);
+            let old_reader_bitmap = self.reader_bitmap.fetch_and(!spot_bit, Ordering::Acquire);
+
+            if old_reader_bitmap & spot_bit == 0 {
+                // Somebody else recycled it
+                continue;
+            }
+
+            // The value may have been recycled and thrown again since
+            // we read the stamp. Now that the spot is ours the stamp is stable.
+            if !is_idle(stamp.load(Ordering::Relaxed)) {
+                self.reader_bitmap.fetch_or(spot_bit, Ordering::Release);
+                continue;
+            }
+
+            let dump_ptr = self.dump.get();
+            let idle = unsafe { (*dump_ptr)[spot as usize] };
+
+            #[cfg(feature = "debug-checks")]
+            if let Some(thrown) = &self.thrown {
+                thrown.remove(idle);
+            }
+
+            self.writer_bitmap.fetch_and(!spot_bit, Ordering::Relaxed);
+
+            f(idle);
+        }
+    }
+
+    /// Returns the number of values in the dump.
+    ///
+    /// Only approximate if other threads are
+    /// concurrently calling `throw()` or `recycle()`.
+    pub fn len(&self) -> usize {
+        self.reader_bitmap.load(Ordering::Relaxed).count_ones() as usize
+    }
+
+    /// Returns the maximum number of values the dump can hold.
+    pub const fn capacity(&self) -> usize {
+        max_bits!(type = usize)
+    }
+
     /// This function is not thread safe.
     ///
+    /// # Safety
+    ///
+    /// No other thread should be calling `throw()` or `recycle()`
+    /// while this executes.
+    ///
     /// This executes closure `f` for every value in the dump
     /// and then clears the dump.
     pub unsafe fn for_each<F>(&self, f: F)
     where
-        F: Fn(*mut T) -> (),
+        F: Fn(*mut T),
     {
         let mut reader_bitmap = self.reader_bitmap.load(Ordering::Relaxed);
 
         self.reader_bitmap.store(0, Ordering::Relaxed);
         self.writer_bitmap.store(0, Ordering::Relaxed);
 
+        #[cfg(feature = "debug-checks")]
+        if let Some(thrown) = &self.thrown {
+            thrown.clear();
+        }
+
         loop {
             // Fast if set bits are sparse which should generally be the case.
             let first_set_spot = reader_bitmap.trailing_zeros();
@@ -0,0 +1,227 @@
+//! Renders [registry](crate::registry) snapshots for monitoring systems.
+//!
+//! [prometheus] is enabled by the `prometheus` feature and [json]
+//! by the `json` feature. Both take the snapshots to render, usually
+//! [registry::snapshot()](crate::registry::snapshot), and return a `String`
+//! to be served by whatever HTTP server the application already has.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "prometheus")] {
+//! use lock_free_freelist::{export, registry, FreeList};
+//!
+//! let free_list = FreeList::<Box<u64>>::new().with_label("example");
+//! drop(free_list.alloc(5));
+//!
+//! let text = export::prometheus(&registry::snapshot());
+//!
+//! assert!(text.contains("freelist_len{type=\"u64\",label=\"example\"} 1\n"));
+//! # }
+//! ```
+
+use super::registry::PoolSnapshot;
+#[cfg(feature = "prometheus")]
+use std::fmt::Write;
+
+/// Name, type, help text and value of a metric.
+#[cfg(feature = "prometheus")]
+type Metric = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&PoolSnapshot) -> u64,
+);
+
+/// Every metric, in the order rendered.
+#[cfg(feature = "prometheus")]
+const METRICS: [Metric; 9] = [
+    (
+        "freelist_reuse_total",
+        "counter",
+        "Pointers taken out of the free list to be reused.",
+        |pool| pool.stats.reuses,
+    ),
+    (
+        "freelist_alloc_total",
+        "counter",
+        "Pointers newly allocated because there was nothing to reuse.",
+        |pool| pool.stats.allocs,
+    ),
+    (
+        "freelist_throw_total",
+        "counter",
+        "Pointers thrown into the free list.",
+        |pool| pool.stats.throws,
+    ),
+    (
+        "freelist_overflow_total",
+        "counter",
+        "Pointers that didn't fit in the free list.",
+        |pool| pool.stats.overflows,
+    ),
+    (
+        "freelist_reject_total",
+        "counter",
+        "Pointers rejected by the return filter.",
+        |pool| pool.stats.rejected,
+    ),
+    (
+        "freelist_cas_retry_total",
+        "counter",
+        "Failed compare and swaps on the bitmaps.",
+        |pool| pool.stats.cas_retries,
+    ),
+    (
+        "freelist_len",
+        "gauge",
+        "Number of pointers in the free list.",
+        |pool| pool.len as u64,
+    ),
+    (
+        "freelist_peak_len",
+        "gauge",
+        "Highest number of pointers the free list has held at once.",
+        |pool| pool.stats.peak_len as u64,
+    ),
+    (
+        "freelist_capacity",
+        "gauge",
+        "Maximum number of pointers the free list can hold.",
+        |pool| pool.capacity as u64,
+    ),
+];
+
+/// Renders `pools` in the Prometheus text exposition format.
+///
+/// Every metric has a `type` label with the type the free list holds
+/// and a `label` label with its [label](crate::FreeList::with_label),
+/// empty if it has none. Free lists with the same type and label are
+/// summed into one series, since a series has to be unique.
+/// The `freelist_peak_len` of such a series is the sum of their peaks.
+#[cfg(feature = "prometheus")]
+pub fn prometheus(pools: &[PoolSnapshot]) -> String {
+    let pools = merge_series(pools);
+    let mut text = String::new();
+
+    for (name, kind, help, value) in METRICS.iter() {
+        let _ = writeln!(text, "# HELP {} {}", name, help);
+        let _ = writeln!(text, "# TYPE {} {}", name, kind);
+
+        for pool in &pools {
+            text.push_str(name);
+            text.push_str("{type=\"");
+            escape_label_value(&mut text, pool.type_name);
+            text.push_str("\",label=\"");
+            escape_label_value(&mut text, pool.label.as_deref().unwrap_or(""));
+            let _ = writeln!(text, "\"}} {}", value(pool));
+        }
+    }
+
+    text
+}
+
+/// Sums the snapshots with the same type and label, keeping the order
+/// in which they first appear.
+#[cfg(feature = "prometheus")]
+fn merge_series(pools: &[PoolSnapshot]) -> Vec<PoolSnapshot> {
+    let mut merged: Vec<PoolSnapshot> = Vec::new();
+
+    for pool in pools {
+        let label = pool.label.as_deref().unwrap_or("");
+        let same_series = merged.iter_mut().find(|series| {
+            series.type_name == pool.type_name && series.label.as_deref().unwrap_or("") == label
+        });
+
+        match same_series {
+            Some(series) => {
+                series.len += pool.len;
+                series.capacity += pool.capacity;
+                series.stats.reuses += pool.stats.reuses;
+                series.stats.allocs += pool.stats.allocs;
+                series.stats.throws += pool.stats.throws;
+                series.stats.overflows += pool.stats.overflows;
+                series.stats.rejected += pool.stats.rejected;
+                series.stats.cas_retries += pool.stats.cas_retries;
+                series.stats.len += pool.stats.len;
+                series.stats.peak_len += pool.stats.peak_len;
+            }
+            None => merged.push(pool.clone()),
+        }
+    }
+
+    merged
+}
+
+#[cfg(feature = "prometheus")]
+fn escape_label_value(text: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => text.push_str("\\\\"),
+            '"' => text.push_str("\\\""),
+            '\n' => text.push_str("\\n"),
+            c => text.push(c),
+        }
+    }
+}
+
+/// Renders `pools` as a JSON array with an object per free list.
+///
+/// ```text
+/// [{"type":"u64","label":null,"len":1,"capacity":64,"peak_len":1,
+///   "reuses":0,"allocs":1,"throws":1,"overflows":0,"rejected":0,"cas_retries":0}]
+/// ```
+#[cfg(feature = "json")]
+pub fn json(pools: &[PoolSnapshot]) -> String {
+    let mut json = String::from("[");
+
+    for (i, pool) in pools.iter().enumerate() {
+        if i != 0 {
+            json.push(',');
+        }
+
+        json.push_str("{\"type\":");
+        escape_string(&mut json, pool.type_name);
+        json.push_str(",\"label\":");
+        match &pool.label {
+            Some(label) => escape_string(&mut json, label),
+            None => json.push_str("null"),
+        }
+        json.push_str(&format!(
+            ",\"len\":{},\"capacity\":{},\"peak_len\":{},\"reuses\":{},\"allocs\":{},\
+             \"throws\":{},\"overflows\":{},\"rejected\":{},\"cas_retries\":{}}}",
+            pool.len,
+            pool.capacity,
+            pool.stats.peak_len,
+            pool.stats.reuses,
+            pool.stats.allocs,
+            pool.stats.throws,
+            pool.stats.overflows,
+            pool.stats.rejected,
+            pool.stats.cas_retries,
+        ));
+    }
+
+    json.push(']');
+    json
+}
+
+/// Pushes `value` as a quoted JSON string.
+#[cfg(feature = "json")]
+fn escape_string(json: &mut String, value: &str) {
+    json.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+}
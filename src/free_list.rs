@@ -1,5 +1,43 @@
-use super::{dump::Dump, reusable::Reusable, reuse::Reuse, smart_pointer::SmartPointer};
-use std::ops::Deref;
+#[cfg(feature = "debug-checks")]
+use super::debug_checks::AddressSet;
+#[cfg(feature = "latency")]
+use super::latency::{Histograms, Latencies};
+#[cfg(feature = "leak-tracking")]
+use super::leak::Outstanding;
+#[cfg(feature = "registry")]
+use super::registry;
+#[cfg(feature = "stats")]
+use super::stats::Stats;
+use super::{
+    drain::Drain,
+    dump::Dump,
+    future::ReuseFuture,
+    idle_trimmer,
+    memory_budget::{Charge, MemoryBudget},
+    overflow::{OverflowPolicy, Spill},
+    quarantine::{Poison, Quarantine},
+    reclaimer::{Deferred, Reclaimer},
+    reusable::Reusable,
+    reuse::Reuse,
+    smart_pointer::SmartPointer,
+    waiters::Waiters,
+};
+use std::{
+    fmt,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Decides whether a returned pointer is kept, see `with_return_filter`.
+type ReturnFilter<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// With the registry feature, the dump is boxed so that the address
+/// the registry refers to doesn't change when the free list is moved.
+#[cfg(feature = "registry")]
+type DumpSlot<T> = Box<Dump<T>>;
+#[cfg(not(feature = "registry"))]
+type DumpSlot<T> = Dump<T>;
 
 /// A dump for throwing and reusing heap
 /// allocated memory. Maximum entries it
@@ -26,7 +64,30 @@ pub struct FreeList<T: SmartPointer>
 where
     <T as Deref>::Target: Sized + Reusable,
 {
-    pub(crate) dump: Dump<<T as Deref>::Target>,
+    pub(crate) dump: DumpSlot<<T as Deref>::Target>,
+    overflow: OverflowPolicy<T>,
+    spill: Spill<<T as Deref>::Target>,
+    deferred: Option<Deferred<T, <T as Deref>::Target>>,
+    /// Number of pointers allocated by this free list which are
+    /// yet to be dropped, whether they are in the free list or in use.
+    pub(crate) live: AtomicUsize,
+    pub(crate) waiters: Waiters,
+    charge: Option<Charge<<T as Deref>::Target>>,
+    label: Option<String>,
+    quarantine: Option<Quarantine<<T as Deref>::Target>>,
+    poison: Option<Poison<<T as Deref>::Target>>,
+    return_filter: Option<ReturnFilter<<T as Deref>::Target>>,
+    /// `Zeroize::zeroize` of the contents, see `with_zeroize`.
+    #[cfg(feature = "zeroize")]
+    wipe: Option<fn(&mut <T as Deref>::Target)>,
+    #[cfg(feature = "latency")]
+    pub(crate) histograms: Histograms,
+    #[cfg(feature = "leak-tracking")]
+    pub(crate) outstanding: Outstanding,
+    /// Pointers allocated or adopted by this free list and not given up since,
+    /// pooled or in use.
+    #[cfg(feature = "debug-checks")]
+    pub(crate) tags: AddressSet,
 }
 
 /// Calls self.clear()
@@ -35,12 +96,50 @@ where
     <T as Deref>::Target: Sized + Reusable,
 {
     fn drop(&mut self) {
+        #[cfg(feature = "registry")]
+        registry::unregister(&*self.dump);
+
         unsafe {
             self.clear();
         }
     }
 }
 
+/// Shows the length, capacity and the bitmaps tracking the free list.
+/// Like [len](crate::FreeList::len), it is only a snapshot when
+/// other threads are using the free list.
+///
+/// # Example
+/// ```
+/// use lock_free_freelist::FreeList;
+///
+/// let free_list = FreeList::<Box<i32>>::new();
+/// drop(free_list.alloc(1));
+///
+/// println!("{:?}", free_list);
+/// ```
+impl<T: SmartPointer> fmt::Debug for FreeList<T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FreeList")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .field("dump", &self.dump)
+            .finish()
+    }
+}
+
+impl<T: SmartPointer> Default for FreeList<T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: SmartPointer> FreeList<T>
 where
     <T as Deref>::Target: Sized + Reusable,
@@ -57,7 +156,257 @@ where
     /// let free_list = FreeList::<Box<MyType>>::new();
     /// ```
     pub fn new() -> Self {
-        FreeList { dump: Dump::new() }
+        let free_list = FreeList {
+            dump: DumpSlot::from(Dump::new()),
+            overflow: OverflowPolicy::Drop,
+            spill: Spill::new(),
+            deferred: None,
+            live: AtomicUsize::new(0),
+            waiters: Waiters::new(),
+            charge: None,
+            label: None,
+            quarantine: None,
+            poison: None,
+            return_filter: None,
+            #[cfg(feature = "zeroize")]
+            wipe: None,
+            #[cfg(feature = "latency")]
+            histograms: Histograms::new(),
+            #[cfg(feature = "leak-tracking")]
+            outstanding: Outstanding::new(),
+            #[cfg(feature = "debug-checks")]
+            tags: AddressSet::new(),
+        };
+
+        #[cfg(feature = "registry")]
+        registry::register(
+            &*free_list.dump,
+            std::any::type_name::<<T as Deref>::Target>(),
+        );
+
+        free_list
+    }
+
+    /// Gives the free list a label, shown in diagnostics
+    /// like the `registry` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<String>>::new().with_label("names");
+    ///
+    /// assert_eq!(free_list.label(), Some("names"));
+    /// ```
+    pub fn with_label(mut self, label: &str) -> Self {
+        #[cfg(feature = "registry")]
+        registry::set_label(&*self.dump, label);
+
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Returns the label given by [with_label](crate::FreeList::with_label).
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Sets what to do with pointers that don't fit in the free list.
+    /// By default they are dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::{FreeList, OverflowPolicy};
+    /// use std::sync::Arc;
+    ///
+    /// let parent = Arc::new(FreeList::<Box<i32>>::new());
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new()
+    ///     .with_overflow_policy(OverflowPolicy::Forward(Arc::clone(&parent)));
+    /// ```
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy<T>) -> Self {
+        self.overflow = policy;
+        self
+    }
+
+    /// Moves destruction off the calling threads.
+    ///
+    /// Pointers dropped because of [OverflowPolicy::Drop](crate::OverflowPolicy::Drop)
+    /// and the old contents replaced in [reuse](crate::FreeList::reuse)
+    /// are sent to `reclaimer` instead of being dropped in place.
+    /// In the latter case, the old contents are swapped out with [std::mem::replace]
+    /// instead of calling [set_new_val](crate::Reusable::set_new_val).
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::{FreeList, Reclaimer};
+    ///
+    /// let reclaimer = Reclaimer::new();
+    ///
+    /// let free_list = FreeList::<Box<String>>::new().with_reclaimer(reclaimer.clone());
+    ///
+    /// drop(free_list.alloc("old".to_string()));
+    ///
+    /// // "old" is dropped by the reclaimer thread
+    /// let reused = free_list.reuse("new".to_string()).unwrap();
+    ///
+    /// reclaimer.flush();
+    /// ```
+    pub fn with_reclaimer(mut self, reclaimer: Reclaimer) -> Self
+    where
+        T: Send + 'static,
+        <T as Deref>::Target: Send + 'static,
+    {
+        self.deferred = Some(Deferred {
+            reclaimer,
+            retire_pointer: Reclaimer::retire::<T>,
+            retire_contents: Reclaimer::retire::<<T as Deref>::Target>,
+        });
+        self
+    }
+
+    /// Charges pointers thrown into the free list against `budget`,
+    /// `size_of::<T::Target>()` bytes each. When the budget is exhausted,
+    /// returned pointers are dropped instead of kept.
+    ///
+    /// Pointers spilled by [OverflowPolicy::Spill](crate::OverflowPolicy::Spill)
+    /// are not charged.
+    pub fn with_memory_budget(self, budget: MemoryBudget) -> Self {
+        self.with_memory_budget_sized(budget, |_| std::mem::size_of::<<T as Deref>::Target>())
+    }
+
+    /// Like [with_memory_budget](crate::FreeList::with_memory_budget) but
+    /// the bytes charged for a pointer are given by `size_of`,
+    /// e.g. the capacity of a buffer.
+    pub fn with_memory_budget_sized<F>(mut self, budget: MemoryBudget, size_of: F) -> Self
+    where
+        F: Fn(&<T as Deref>::Target) -> usize + Send + Sync + 'static,
+    {
+        self.charge = Some(Charge {
+            budget,
+            size_of: Box::new(size_of),
+        });
+        self
+    }
+
+    /// Drops returned pointers for which `filter` returns false instead
+    /// of keeping them, e.g. buffers that grew too big to be worth keeping
+    /// or objects left in an invalid state.
+    ///
+    /// Rejections are counted by [stats](crate::FreeList::stats) with the `stats` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<String>>::new()
+    ///     .with_return_filter(|string| string.capacity() <= 1024);
+    ///
+    /// drop(free_list.alloc("a".repeat(4096)));
+    /// assert_eq!(free_list.len(), 0);
+    ///
+    /// drop(free_list.alloc("a".to_string()));
+    /// assert_eq!(free_list.len(), 1);
+    /// ```
+    pub fn with_return_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&<T as Deref>::Target) -> bool + Send + Sync + 'static,
+    {
+        self.return_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Holds returned pointers back until `len` more have been returned
+    /// after them, instead of making them available for reuse right away.
+    ///
+    /// This is a debugging aid. Code that keeps using an object through
+    /// a raw pointer after its [Reuse](crate::Reuse) is dropped usually goes
+    /// unnoticed because the object is reused with valid contents soon after.
+    /// While a pointer is quarantined its contents are dropped and its memory
+    /// is marked uninitialized, so such accesses fail in tests or under Miri.
+    /// It gets [Default] contents when it is released for reuse.
+    ///
+    /// Quarantined pointers are not counted in [len](crate::FreeList::len)
+    /// and are not taken by [drain](crate::FreeList::drain) or trims.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new().with_quarantine(1);
+    ///
+    /// drop(free_list.alloc(1));
+    /// assert!(free_list.reuse(2).is_err());
+    ///
+    /// // the first pointer is released by the next return
+    /// drop(free_list.alloc(3));
+    /// assert!(free_list.reuse(2).is_ok());
+    /// ```
+    pub fn with_quarantine(mut self, len: usize) -> Self
+    where
+        <T as Deref>::Target: Default,
+    {
+        self.quarantine = if len > 0 {
+            Some(Quarantine::new(len, Default::default))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Calls `poison` on the contents of every pointer returned
+    /// to the free list, before it is kept or quarantined.
+    ///
+    /// Contents must stay valid values of their type, so `poison` should
+    /// overwrite them with a recognizable one such as a buffer filled
+    /// with `0xdd` or an id of `u64::MAX`. With a
+    /// [quarantine](crate::FreeList::with_quarantine), this runs before
+    /// the contents are dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<Vec<u8>>>::new().with_poison(|buf| {
+    ///     for byte in buf.iter_mut() {
+    ///         *byte = 0xdd;
+    ///     }
+    /// });
+    /// ```
+    pub fn with_poison<F>(mut self, poison: F) -> Self
+    where
+        F: Fn(&mut <T as Deref>::Target) + Send + Sync + 'static,
+    {
+        self.poison = Some(Box::new(poison));
+        self
+    }
+
+    /// Wipes the contents of every pointer returned to the free list with
+    /// [Zeroize](zeroize::Zeroize), so that secrets like keys and tokens
+    /// don't linger in pooled memory until the pointer is reused.
+    ///
+    /// Pointers still in the free list are wiped again when it is
+    /// [cleared](crate::FreeList::clear) or dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<Vec<u8>>>::new().with_zeroize();
+    ///
+    /// let mut key = free_list.alloc(Vec::with_capacity(32));
+    /// key.extend_from_slice(b"secret");
+    ///
+    /// // the bytes are zeroed before the buffer is kept for reuse
+    /// drop(key);
+    /// ```
+    #[cfg(feature = "zeroize")]
+    pub fn with_zeroize(mut self) -> Self
+    where
+        <T as Deref>::Target: zeroize::Zeroize,
+    {
+        self.wipe = Some(<<T as Deref>::Target as zeroize::Zeroize>::zeroize);
+        self
     }
 
     /// Returns a [Reuse](crate::Reuse) on success.
@@ -93,14 +442,62 @@ where
         &'a self,
         contents: <T as Deref>::Target,
     ) -> Result<Reuse<'a, T>, <T as Deref>::Target> {
-        if let Ok(ptr) = self.dump.recycle() {
-            let mut reused = unsafe { T::from_raw(ptr) };
-            reused.set_new_val(contents);
+        timed!(self, reuse, {
+            if let Some(ptr) = self.recycle() {
+                trace_event!(self, TRACE, "reuse hit");
 
-            Ok(Reuse::new(reused, self))
-        } else {
-            Err(contents)
-        }
+                let mut reused = unsafe { T::from_raw(ptr) };
+
+                match &self.deferred {
+                    Some(deferred) => {
+                        let old_contents = std::mem::replace(&mut *reused, contents);
+                        (deferred.retire_contents)(&deferred.reclaimer, old_contents);
+                    }
+                    None => reused.set_new_val(contents),
+                }
+
+                Ok(Reuse::from_live(reused, self))
+            } else {
+                trace_event!(self, TRACE, "reuse miss");
+
+                Err(contents)
+            }
+        })
+    }
+
+    /// Waits for a free pointer instead of returning the contents back
+    /// if the free list is empty.
+    ///
+    /// The returned future completes when a [Reuse](crate::Reuse)
+    /// is dropped and its pointer thrown back into the free list.
+    /// It doesn't depend on any particular async runtime.
+    ///
+    /// # Example
+    /// ```
+    /// # fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    /// #     use std::{sync::Arc, task::{Context, Poll, Wake}, thread::{self, Thread}};
+    /// #     struct Unparker(Thread);
+    /// #     impl Wake for Unparker { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// #     let waker = Arc::new(Unparker(thread::current())).into();
+    /// #     let mut f = Box::pin(f);
+    /// #     loop {
+    /// #         match f.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(output) => return output,
+    /// #             Poll::Pending => thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    ///
+    /// drop(free_list.alloc(5));
+    ///
+    /// let reused = block_on(free_list.reuse_async(9));
+    /// assert_eq!(**reused, 9);
+    /// ```
+    pub fn reuse_async(&self, contents: <T as Deref>::Target) -> ReuseFuture<'_, T> {
+        ReuseFuture::new(self, contents)
     }
 
     /// Reuses pointers from free list if it is not empty, otherwise
@@ -147,8 +544,318 @@ where
     /// let x = free_list.alloc(5);
     /// ```
     pub fn alloc<'a>(&'a self, contents: <T as Deref>::Target) -> Reuse<'a, T> {
-        let allocated = T::new(contents);
-        Reuse::new(allocated, self)
+        timed!(self, alloc, {
+            record_stat!(self.dump, allocs);
+
+            let allocated = T::new(contents);
+            Reuse::new(allocated, self)
+        })
+    }
+
+    /// Allocates up to `n` new pointers with contents given by `factory`
+    /// and puts them in the free list, stopping early if it gets full.
+    ///
+    /// Returns the number of pointers added.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<String>>::new();
+    ///
+    /// assert_eq!(free_list.prefill(8, || String::with_capacity(64)), 8);
+    ///
+    /// assert!(free_list.reuse("hello".to_string()).is_ok());
+    /// ```
+    pub fn prefill<F>(&self, n: usize, mut factory: F) -> usize
+    where
+        F: FnMut() -> <T as Deref>::Target,
+    {
+        let mut added = 0;
+
+        while added < n {
+            let ptr = T::into_raw(T::new(factory()));
+
+            if !self.charge(ptr) {
+                unsafe {
+                    let _to_drop = T::from_raw(ptr);
+                }
+                break;
+            }
+
+            if let Err(ptr) = self.dump.throw(ptr) {
+                self.refund(ptr);
+                unsafe {
+                    let _to_drop = T::from_raw(ptr);
+                }
+                break;
+            }
+
+            self.live.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "debug-checks")]
+            self.tags.insert(ptr);
+            added += 1;
+        }
+
+        trace_event!(self, DEBUG, "prefill", requested = n, added = added);
+
+        added
+    }
+
+    /// Returns a snapshot of the counters of this free list.
+    ///
+    /// The counters are kept per thread so that
+    /// updating them doesn't add contention.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    ///
+    /// drop(free_list.reuse_or_alloc(1));
+    /// drop(free_list.reuse_or_alloc(2));
+    ///
+    /// let stats = free_list.stats();
+    /// assert_eq!(stats.allocs, 1);
+    /// assert_eq!(stats.reuses, 1);
+    /// assert_eq!(stats.len, 1);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.dump.counters.snapshot(self.len())
+    }
+
+    /// Returns the latency percentiles of [reuse](crate::FreeList::reuse),
+    /// [alloc](crate::FreeList::alloc) and dropping a [Reuse](crate::Reuse).
+    ///
+    /// Every call to these reads the clock twice, which is why
+    /// this needs the `latency` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    ///
+    /// drop(free_list.alloc(5));
+    /// drop(free_list.reuse(9));
+    ///
+    /// let latencies = free_list.latencies();
+    /// assert_eq!(latencies.alloc.count, 1);
+    /// assert_eq!(latencies.reuse.count, 1);
+    /// assert_eq!(latencies.drop.count, 2);
+    /// assert!(latencies.reuse.p50 <= latencies.reuse.max);
+    /// ```
+    #[cfg(feature = "latency")]
+    pub fn latencies(&self) -> Latencies {
+        self.histograms.snapshot()
+    }
+
+    /// Returns the number of [Reuse](crate::Reuse) handles
+    /// of this free list that haven't been dropped yet.
+    ///
+    /// Handles that were [forgotten](std::mem::forget) stay outstanding forever.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    ///
+    /// let x = free_list.alloc(5);
+    /// assert_eq!(free_list.outstanding(), 1);
+    ///
+    /// drop(x);
+    /// assert_eq!(free_list.outstanding(), 0);
+    /// ```
+    #[cfg(feature = "leak-tracking")]
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.count()
+    }
+
+    /// Describes the outstanding [Reuse](crate::Reuse) handles,
+    /// or returns None if there are none.
+    ///
+    /// This is what [clear](crate::FreeList::clear), and so dropping
+    /// the free list, prints to stderr. With the `leak-backtraces` feature
+    /// it includes the backtrace of where each handle was created.
+    #[cfg(feature = "leak-tracking")]
+    pub fn leak_report(&self) -> Option<String> {
+        self.outstanding.report(self.type_name(), self.label())
+    }
+
+    /// Returns the number of free pointers in the free list.
+    /// Pointers spilled by [OverflowPolicy::Spill](crate::OverflowPolicy::Spill)
+    /// are not counted.
+    ///
+    /// This is safe to call while other threads use the free list,
+    /// but then the result is only approximate since
+    /// they may have thrown or reused pointers by the time it returns.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    /// assert_eq!(free_list.len(), 0);
+    ///
+    /// drop(free_list.alloc(1));
+    /// assert_eq!(free_list.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.dump.len()
+    }
+
+    /// Returns true if there are no free pointers in the free list.
+    /// Approximate in the same way as [len](crate::FreeList::len).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the free list can't take any more pointers.
+    /// Approximate in the same way as [len](crate::FreeList::len).
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Returns the maximum number of free pointers the free list can hold,
+    /// which is the number of bits in `usize`.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    /// assert_eq!(free_list.capacity(), std::mem::size_of::<usize>() * 8);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.dump.capacity()
+    }
+
+    /// Returns an iterator that takes the free pointers out of the free list.
+    ///
+    /// Unlike [clear](crate::FreeList::clear), this is thread safe.
+    /// Other threads can keep using the free list while it is drained,
+    /// and the iterator ends when it finds the free list empty.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    ///
+    /// drop(free_list.alloc(1));
+    /// drop(free_list.alloc(2));
+    ///
+    /// let boxes: Vec<Box<i32>> = free_list.drain().collect();
+    ///
+    /// assert_eq!(boxes.len(), 2);
+    /// assert!(free_list.is_empty());
+    /// ```
+    pub fn drain(&self) -> Drain<'_, T> {
+        Drain::new(self)
+    }
+
+    /// Drops free pointers until at most `len` are left in the free list.
+    /// Pointers spilled by [OverflowPolicy::Spill](crate::OverflowPolicy::Spill)
+    /// are all dropped.
+    ///
+    /// Returns the number of pointers dropped. Like [drain](crate::FreeList::drain),
+    /// this can be called while other threads are using the free list.
+    /// If there is a [Reclaimer](crate::Reclaimer), the pointers are dropped there.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    /// free_list.prefill(10, || 0);
+    ///
+    /// assert_eq!(free_list.trim_to(4), 6);
+    /// assert_eq!(free_list.len(), 4);
+    /// ```
+    pub fn trim_to(&self, len: usize) -> usize {
+        let mut trimmed = 0;
+
+        for ptr in self.spill.take_all() {
+            self.drop_pointer(ptr);
+            trimmed += 1;
+        }
+
+        while self.len() > len {
+            match self.recycle_from_dump() {
+                Some(ptr) => {
+                    self.drop_pointer(ptr);
+                    trimmed += 1;
+                }
+                None => break,
+            }
+        }
+
+        if trimmed > 0 {
+            trace_event!(self, DEBUG, "trim", len = len, trimmed = trimmed);
+            self.waiters.notify_all();
+        }
+
+        trimmed
+    }
+
+    /// Drops the free pointers that have been sitting in the free list
+    /// for longer than `max_age`. Returns the number of pointers dropped.
+    ///
+    /// A pointer's idle time is counted from the first call that sees it
+    /// in the free list, so calling this periodically drops a pointer
+    /// somewhere between `max_age` and `max_age` plus the period after it
+    /// was thrown. This way [Reuse](crate::Reuse) doesn't have to read the clock
+    /// when it is dropped. See [IdleTrimmer](crate::IdleTrimmer) to do it in the background.
+    ///
+    /// Other threads can keep using the free list meanwhile.
+    /// Pointers spilled by [OverflowPolicy::Spill](crate::OverflowPolicy::Spill)
+    /// are not considered.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::FreeList;
+    /// use std::{thread, time::Duration};
+    ///
+    /// let free_list = FreeList::<Box<i32>>::new();
+    /// free_list.prefill(4, || 0);
+    ///
+    /// // first call only notes the pointers
+    /// assert_eq!(free_list.trim_idle(Duration::from_millis(1)), 0);
+    ///
+    /// thread::sleep(Duration::from_millis(5));
+    /// assert_eq!(free_list.trim_idle(Duration::from_millis(1)), 4);
+    /// ```
+    pub fn trim_idle(&self, max_age: Duration) -> usize {
+        let mut trimmed = 0;
+        let max_age_ms = max_age.as_millis().min(u64::MAX as u128) as u64;
+
+        self.dump
+            .recycle_idle(idle_trimmer::now_millis(), max_age_ms, |ptr| {
+                self.refund(ptr);
+                self.drop_pointer(ptr);
+                trimmed += 1;
+            });
+
+        if trimmed > 0 {
+            trace_event!(
+                self,
+                DEBUG,
+                "idle trim",
+                max_age_ms = max_age_ms,
+                trimmed = trimmed
+            );
+            self.waiters.notify_all();
+        }
+
+        trimmed
+    }
+
+    /// Drops all the free pointers, same as `trim_to(0)`.
+    pub fn shrink(&self) -> usize {
+        self.trim_to(0)
     }
 
     /// Calls drop for all the pointers in free list
@@ -156,6 +863,11 @@ where
     ///
     /// This is not thread safe.
     ///
+    /// # Safety
+    ///
+    /// No other thread should be using the free list
+    /// while it is being cleared.
+    ///
     /// # Example
     /// ```
     /// use lock_free_freelist::FreeList;
@@ -167,9 +879,201 @@ where
     /// }
     /// ```
     pub unsafe fn clear(&self) {
+        #[cfg(feature = "leak-tracking")]
+        if let Some(report) = self.leak_report() {
+            eprintln!("{}", report);
+        }
+
+        trace_event!(self, DEBUG, "clear", len = self.len());
+
         // drop all the pointers that are still on free list
         self.dump.for_each(|ptr| {
+            self.refund(ptr);
+            #[cfg(feature = "zeroize")]
+            self.wipe(ptr);
+            self.live.fetch_sub(1, Ordering::Relaxed);
+            #[cfg(feature = "debug-checks")]
+            self.tags.remove(ptr);
             let _ = T::from_raw(ptr);
         });
+
+        while let Some(ptr) = self.spill.pop() {
+            #[cfg(feature = "zeroize")]
+            self.wipe(ptr);
+            self.live.fetch_sub(1, Ordering::Relaxed);
+            #[cfg(feature = "debug-checks")]
+            self.tags.remove(ptr);
+            let _ = T::from_raw(ptr);
+        }
+
+        if let Some(quarantine) = &self.quarantine {
+            while let Some(ptr) = quarantine.pop() {
+                self.live.fetch_sub(1, Ordering::Relaxed);
+                #[cfg(feature = "debug-checks")]
+                self.tags.remove(ptr);
+                let _ = T::from_raw(ptr);
+            }
+        }
+
+        self.waiters.notify_all();
+    }
+
+    /// Gets a free pointer from the dump and if that is empty,
+    /// from the spilled pointers.
+    pub(crate) fn take(&self) -> Option<*mut <T as Deref>::Target> {
+        self.recycle_from_dump().or_else(|| match self.overflow {
+            OverflowPolicy::Spill => self.spill.pop(),
+            _ => None,
+        })
+    }
+
+    /// Gets a free pointer from the dump, giving back
+    /// what it was charged to the memory budget.
+    fn recycle_from_dump(&self) -> Option<*mut <T as Deref>::Target> {
+        let ptr = self.dump.recycle().ok()?;
+        self.refund(ptr);
+        Some(ptr)
+    }
+
+    /// Like [take](FreeList::take), but counted as a reuse.
+    pub(crate) fn recycle(&self) -> Option<*mut <T as Deref>::Target> {
+        let recycled = self.take();
+
+        if recycled.is_some() {
+            record_stat!(self.dump, reuses);
+        }
+
+        recycled
+    }
+
+    /// Stores `ptr` in the free list. If the free list is full,
+    /// the [OverflowPolicy](crate::OverflowPolicy) decides its fate.
+    ///
+    /// With the `debug-checks` feature, it panics if the free list
+    /// didn't allocate or adopt `ptr`.
+    pub(crate) fn throw(&self, ptr: *mut <T as Deref>::Target) {
+        #[cfg(feature = "debug-checks")]
+        assert!(
+            self.tags.contains(ptr),
+            "pointer {:p} thrown into a free list that didn't allocate it, \
+             check that SmartPointer::into_raw of {} gives back the pointer \
+             passed to SmartPointer::from_raw",
+            ptr,
+            std::any::type_name::<T>()
+        );
+
+        let accepted = match &self.return_filter {
+            Some(filter) => filter(unsafe { &*ptr }),
+            None => true,
+        };
+
+        #[cfg(feature = "zeroize")]
+        self.wipe(ptr);
+
+        if !accepted {
+            record_stat!(self.dump, rejected);
+            trace_event!(self, DEBUG, "rejected by return filter, dropping pointer");
+            self.drop_pointer(ptr);
+            self.waiters.notify_fenced();
+            return;
+        }
+
+        if let Some(poison) = &self.poison {
+            poison(unsafe { &mut *ptr });
+        }
+
+        let ptr = match &self.quarantine {
+            Some(quarantine) => match unsafe { quarantine.push(ptr) } {
+                Some(released) => released,
+                // Nothing became available for reuse
+                None => return,
+            },
+            None => ptr,
+        };
+
+        if !self.charge(ptr) {
+            trace_event!(self, DEBUG, "memory budget exhausted, dropping pointer");
+            self.drop_pointer(ptr);
+            self.waiters.notify_fenced();
+            return;
+        }
+
+        if let Err(ptr) = self.dump.throw(ptr) {
+            self.refund(ptr);
+            record_stat!(self.dump, overflows);
+
+            match &self.overflow {
+                OverflowPolicy::Drop => {
+                    trace_event!(self, DEBUG, "free list full, dropping pointer");
+                    self.drop_pointer(ptr)
+                }
+                OverflowPolicy::Spill => self.spill.push(ptr),
+                OverflowPolicy::Forward(free_list) => {
+                    self.live.fetch_sub(1, Ordering::Relaxed);
+                    free_list.live.fetch_add(1, Ordering::Relaxed);
+                    #[cfg(feature = "debug-checks")]
+                    {
+                        self.tags.remove(ptr);
+                        free_list.tags.insert(ptr);
+                    }
+                    free_list.throw(ptr);
+                }
+                OverflowPolicy::Callback(callback) => {
+                    self.live.fetch_sub(1, Ordering::Relaxed);
+                    #[cfg(feature = "debug-checks")]
+                    self.tags.remove(ptr);
+                    callback(unsafe { T::from_raw(ptr) });
+                }
+            }
+
+            self.waiters.notify_fenced();
+            return;
+        }
+
+        self.waiters.notify();
+    }
+
+    /// Name of the type the free list holds, for diagnostics.
+    #[cfg(any(feature = "tracing", feature = "leak-tracking"))]
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<<T as Deref>::Target>()
+    }
+
+    /// Zeroizes the contents of `ptr` if [with_zeroize](crate::FreeList::with_zeroize) was called.
+    #[cfg(feature = "zeroize")]
+    fn wipe(&self, ptr: *mut <T as Deref>::Target) {
+        if let Some(wipe) = self.wipe {
+            wipe(unsafe { &mut *ptr });
+        }
+    }
+
+    /// Drops the smart pointer owning `ptr`, on the reclaimer thread if there is one.
+    fn drop_pointer(&self, ptr: *mut <T as Deref>::Target) {
+        self.live.fetch_sub(1, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        self.tags.remove(ptr);
+
+        let smart_pointer = unsafe { T::from_raw(ptr) };
+
+        match &self.deferred {
+            Some(deferred) => (deferred.retire_pointer)(&deferred.reclaimer, smart_pointer),
+            None => drop(smart_pointer),
+        }
+    }
+
+    /// Charges the memory budget, if any, for keeping `ptr` in the dump.
+    /// Returns false if the budget is exhausted.
+    fn charge(&self, ptr: *mut <T as Deref>::Target) -> bool {
+        match &self.charge {
+            Some(charge) => charge.budget.try_charge((charge.size_of)(unsafe { &*ptr })),
+            None => true,
+        }
+    }
+
+    /// Gives back what was charged for keeping `ptr` in the dump.
+    fn refund(&self, ptr: *mut <T as Deref>::Target) {
+        if let Some(charge) = &self.charge {
+            charge.budget.refund((charge.size_of)(unsafe { &*ptr }));
+        }
     }
 }
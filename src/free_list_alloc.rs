@@ -0,0 +1,171 @@
+use super::dump::Dump;
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    fmt, ptr,
+};
+
+/// Smallest and largest size classes, as powers of two.
+const MIN_CLASS: u32 = 4;
+const MAX_CLASS: u32 = 10;
+
+/// Blocks of every size class are aligned to this.
+const MAX_ALIGN: usize = 16;
+
+const CLASSES: usize = (MAX_CLASS - MIN_CLASS + 1) as usize;
+
+/// A [GlobalAlloc] that caches small blocks in lock free dumps
+/// in front of another allocator.
+///
+/// Allocations of up to 1 KiB with an alignment of up to 16 are rounded
+/// up to a power of two size class. Freed blocks are kept in the dump of
+/// their class, as many as there are bits in `usize`, and handed out again
+/// by later allocations of the class. Misses, overflows and all other
+/// allocations go to the inner allocator.
+///
+/// Cached blocks are never given back to the inner allocator while the
+/// `FreeListAlloc` is alive, which is forever for a global allocator.
+/// The cache is at most a few hundred KiB.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::FreeListAlloc;
+/// use std::alloc::System;
+///
+/// #[global_allocator]
+/// static GLOBAL: FreeListAlloc<System> = FreeListAlloc::new(System);
+///
+/// fn main() {
+///     let boxed = Box::new([0u8; 100]);
+///     drop(boxed);
+///
+///     assert!(GLOBAL.cached() >= 1);
+/// }
+/// ```
+pub struct FreeListAlloc<A: GlobalAlloc> {
+    inner: A,
+    /// `classes[i]` holds blocks of `1 << (MIN_CLASS + i)` bytes.
+    classes: [Dump<u8>; CLASSES],
+}
+
+impl<A: GlobalAlloc> FreeListAlloc<A> {
+    /// Puts a cache in front of `inner`.
+    pub const fn new(inner: A) -> Self {
+        FreeListAlloc {
+            inner,
+            // The dumps must not allocate, see `Dump::new_unchecked()`
+            classes: [
+                Dump::new_unchecked(),
+                Dump::new_unchecked(),
+                Dump::new_unchecked(),
+                Dump::new_unchecked(),
+                Dump::new_unchecked(),
+                Dump::new_unchecked(),
+                Dump::new_unchecked(),
+            ],
+        }
+    }
+
+    /// Returns the number of free blocks in the cache.
+    ///
+    /// Only approximate if other threads are allocating.
+    pub fn cached(&self) -> usize {
+        self.classes.iter().map(|dump| dump.len()).sum()
+    }
+
+    /// Returns the size class of `layout`, or None if it isn't cached.
+    fn class_of(layout: Layout) -> Option<u32> {
+        if layout.size() > 1 << MAX_CLASS || layout.align() > MAX_ALIGN {
+            return None;
+        }
+
+        let class = layout.size().next_power_of_two().trailing_zeros();
+
+        Some(class.max(MIN_CLASS))
+    }
+
+    /// Layout of the blocks of `class` in the inner allocator.
+    fn class_layout(class: u32) -> Layout {
+        unsafe { Layout::from_size_align_unchecked(1 << class, MAX_ALIGN) }
+    }
+
+    fn dump(&self, class: u32) -> &Dump<u8> {
+        &self.classes[(class - MIN_CLASS) as usize]
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for FreeListAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match Self::class_of(layout) {
+            Some(class) => match self.dump(class).recycle() {
+                Ok(block) => block,
+                Err(()) => self.inner.alloc(Self::class_layout(class)),
+            },
+            None => self.inner.alloc(layout),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        match Self::class_of(layout) {
+            Some(class) => {
+                if let Err(block) = self.dump(class).throw(ptr) {
+                    self.inner.dealloc(block, Self::class_layout(class));
+                }
+            }
+            None => self.inner.dealloc(ptr, layout),
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if Self::class_of(layout).is_none() {
+            return self.inner.alloc_zeroed(layout);
+        }
+
+        let block = self.alloc(layout);
+
+        if !block.is_null() {
+            ptr::write_bytes(block, 0, layout.size());
+        }
+
+        block
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+
+        match (Self::class_of(layout), Self::class_of(new_layout)) {
+            // The block is big enough already
+            (Some(old_class), Some(new_class)) if old_class == new_class => ptr,
+            (None, None) => self.inner.realloc(ptr, layout, new_size),
+            _ => {
+                let new_ptr = self.alloc(new_layout);
+
+                if !new_ptr.is_null() {
+                    ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                    self.dealloc(ptr, layout);
+                }
+
+                new_ptr
+            }
+        }
+    }
+}
+
+/// Gives the cached blocks back to the inner allocator.
+impl<A: GlobalAlloc> Drop for FreeListAlloc<A> {
+    fn drop(&mut self) {
+        for (dump, class) in self.classes.iter().zip(MIN_CLASS..) {
+            unsafe {
+                dump.for_each(|block| self.inner.dealloc(block, Self::class_layout(class)));
+            }
+        }
+    }
+}
+
+impl<A: GlobalAlloc> fmt::Debug for FreeListAlloc<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FreeListAlloc")
+            .field("cached", &self.cached())
+            .finish()
+    }
+}
@@ -0,0 +1,130 @@
+use super::{
+    free_list::FreeList, pool::Pool, reusable::Reusable, reuse::Reuse, smart_pointer::SmartPointer,
+    waiters::Registration,
+};
+use std::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Future returned by [FreeList::reuse_async](crate::FreeList::reuse_async).
+///
+/// It completes once it finds a free pointer in the free list.
+/// Pointers are not reserved for a particular future, so
+/// dropping it before it completes can't lose one.
+#[must_use = "futures do nothing unless polled"]
+pub struct ReuseFuture<'a, T: SmartPointer>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    free_list: &'a FreeList<T>,
+    contents: Option<<T as Deref>::Target>,
+    registration: Registration,
+}
+
+impl<'a, T: SmartPointer> ReuseFuture<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    pub(crate) fn new(free_list: &'a FreeList<T>, contents: <T as Deref>::Target) -> Self {
+        ReuseFuture {
+            free_list,
+            contents: Some(contents),
+            registration: Registration::default(),
+        }
+    }
+}
+
+/// The contents are never pinned.
+impl<'a, T: SmartPointer> Unpin for ReuseFuture<'a, T> where <T as Deref>::Target: Sized + Reusable {}
+
+impl<'a, T: SmartPointer> Future for ReuseFuture<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    type Output = Reuse<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let free_list = this.free_list;
+        let contents = &mut this.contents;
+
+        free_list.waiters.poll_take(cx, &mut this.registration, || {
+            let to_reuse = contents
+                .take()
+                .expect("ReuseFuture polled after completion");
+
+            match free_list.reuse(to_reuse) {
+                Ok(reused) => Some(reused),
+                Err(to_reuse) => {
+                    *contents = Some(to_reuse);
+                    None
+                }
+            }
+        })
+    }
+}
+
+impl<'a, T: SmartPointer> Drop for ReuseFuture<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    fn drop(&mut self) {
+        self.free_list.waiters.deregister(&mut self.registration);
+    }
+}
+
+/// Future returned by [Pool::get_async](crate::Pool::get_async).
+///
+/// It completes once an object is returned to the pool or the pool
+/// is allowed to create one. Dropping it before it completes can't lose an object.
+#[must_use = "futures do nothing unless polled"]
+pub struct GetFuture<'a, T: SmartPointer>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    pool: &'a Pool<T>,
+    registration: Registration,
+}
+
+impl<'a, T: SmartPointer> GetFuture<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    pub(crate) fn new(pool: &'a Pool<T>) -> Self {
+        GetFuture {
+            pool,
+            registration: Registration::default(),
+        }
+    }
+}
+
+impl<'a, T: SmartPointer> Future for GetFuture<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    type Output = Reuse<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let pool = this.pool;
+
+        pool.free_list()
+            .waiters
+            .poll_take(cx, &mut this.registration, || pool.try_get())
+    }
+}
+
+impl<'a, T: SmartPointer> Drop for GetFuture<'a, T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    fn drop(&mut self) {
+        self.pool
+            .free_list()
+            .waiters
+            .deregister(&mut self.registration);
+    }
+}
@@ -0,0 +1,61 @@
+use super::{
+    free_list::FreeList, reusable::Reusable, smart_pointer::SmartPointer, worker::Periodic,
+};
+use std::{
+    ops::Deref,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Coarse clock for idle trimming, in milliseconds.
+/// It starts at 1 since a stamp of 0 means the pointer hasn't been seen yet.
+pub(crate) fn now_millis() -> u64 {
+    let elapsed = START.get_or_init(Instant::now).elapsed().as_millis();
+    elapsed.min(u64::MAX as u128 - 1) as u64 + 1
+}
+
+/// A background thread that calls [FreeList::trim_idle](crate::FreeList::trim_idle)
+/// every `interval`, so that pointers idle in the free list for longer
+/// than `max_age` are dropped some time between `max_age` and `max_age + interval`.
+///
+/// The free list can be given as an [Arc](std::sync::Arc) or a `&'static` reference.
+///
+/// The thread is stopped when the `IdleTrimmer` is dropped.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::{FreeList, IdleTrimmer};
+/// use std::{sync::Arc, time::Duration};
+///
+/// let free_list = Arc::new(FreeList::<Box<Vec<u8>>>::new());
+///
+/// let trimmer = IdleTrimmer::spawn(
+///     Arc::clone(&free_list),
+///     Duration::from_secs(60),
+///     Duration::from_secs(10),
+/// );
+/// ```
+pub struct IdleTrimmer {
+    _worker: Periodic,
+}
+
+impl IdleTrimmer {
+    /// Spawns the trimmer thread for `free_list`.
+    ///
+    /// Idle pointers are dropped on that thread, hence the `Send` bounds.
+    pub fn spawn<L, T>(free_list: L, max_age: Duration, interval: Duration) -> Self
+    where
+        L: Deref<Target = FreeList<T>> + Send + 'static,
+        T: SmartPointer + Send,
+        <T as Deref>::Target: Sized + Reusable + Send,
+    {
+        let worker = Periodic::spawn("freelist-idle-trimmer", interval, move || {
+            free_list.trim_idle(max_age);
+        });
+
+        IdleTrimmer { _worker: worker }
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Latency percentiles of one operation,
+/// part of [Latencies](crate::Latencies).
+///
+/// Latencies are recorded into buckets of powers of two nanoseconds, so
+/// the percentiles are upper bounds at most twice the actual latency.
+/// `max` is exact.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Latency {
+    /// Number of operations recorded.
+    pub count: u64,
+    pub p50: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub max: Duration,
+}
+
+/// Latencies of the operations of a [FreeList](crate::FreeList),
+/// returned by [FreeList::latencies](crate::FreeList::latencies).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Latencies {
+    /// [FreeList::reuse](crate::FreeList::reuse), whether it found a pointer or not.
+    pub reuse: Latency,
+    /// [FreeList::alloc](crate::FreeList::alloc).
+    pub alloc: Latency,
+    /// Dropping a [Reuse](crate::Reuse), which throws its pointer back
+    /// or hands it to the [OverflowPolicy](crate::OverflowPolicy).
+    pub drop: Latency,
+}
+
+/// Bucket 0 counts latencies of 0ns and bucket `i`
+/// those in `[2^(i - 1), 2^i)` nanoseconds.
+const BUCKETS: usize = 65;
+
+/// A lock free histogram of latencies.
+pub(crate) struct Histogram {
+    buckets: [AtomicU64; BUCKETS],
+    max: AtomicU64,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_BUCKET: AtomicU64 = AtomicU64::new(0);
+
+impl Histogram {
+    pub const fn new() -> Self {
+        Histogram {
+            buckets: [EMPTY_BUCKET; BUCKETS],
+            max: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, latency: Duration) {
+        let nanos = latency.as_nanos().min(u64::MAX as u128) as u64;
+        let bucket = (u64::BITS - nanos.leading_zeros()) as usize;
+
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+
+        if nanos > self.max.load(Ordering::Relaxed) {
+            self.max.fetch_max(nanos, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self) -> Latency {
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        let max = self.max.load(Ordering::Relaxed);
+        let count = buckets.iter().sum::<u64>();
+
+        // Upper bound of the bucket holding the latency of the given rank,
+        // capped by the max since it can't be exceeded.
+        let percentile = |quantile: f64| {
+            if count == 0 {
+                return Duration::ZERO;
+            }
+
+            let rank = ((count as f64 * quantile).ceil() as u64).max(1);
+            let mut seen = 0;
+
+            for (bucket, n) in buckets.iter().enumerate() {
+                seen += n;
+
+                if seen >= rank {
+                    let upper_bound = match bucket {
+                        0 => 0,
+                        64 => u64::MAX,
+                        _ => (1 << bucket) - 1,
+                    };
+                    return Duration::from_nanos(upper_bound.min(max));
+                }
+            }
+
+            Duration::from_nanos(max)
+        };
+
+        Latency {
+            count,
+            p50: percentile(0.5),
+            p99: percentile(0.99),
+            p999: percentile(0.999),
+            max: Duration::from_nanos(max),
+        }
+    }
+}
+
+/// Histograms of a [FreeList](crate::FreeList).
+pub(crate) struct Histograms {
+    pub reuse: Histogram,
+    pub alloc: Histogram,
+    pub drop: Histogram,
+}
+
+impl Histograms {
+    pub const fn new() -> Self {
+        Histograms {
+            reuse: Histogram::new(),
+            alloc: Histogram::new(),
+            drop: Histogram::new(),
+        }
+    }
+
+    pub fn snapshot(&self) -> Latencies {
+        Latencies {
+            reuse: self.reuse.snapshot(),
+            alloc: self.alloc.snapshot(),
+            drop: self.drop.snapshot(),
+        }
+    }
+}
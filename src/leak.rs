@@ -0,0 +1,76 @@
+#[cfg(feature = "leak-backtraces")]
+use std::{backtrace::Backtrace, collections::HashMap, sync::Mutex};
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// [Reuse](crate::Reuse) handles of a [FreeList](crate::FreeList)
+/// that haven't been dropped yet.
+///
+/// With the `leak-backtraces` feature, the backtrace of where each
+/// handle was created is kept too, keyed by the address of its contents.
+pub(crate) struct Outstanding {
+    count: AtomicUsize,
+    #[cfg(feature = "leak-backtraces")]
+    backtraces: Mutex<HashMap<usize, Backtrace>>,
+}
+
+impl Outstanding {
+    pub fn new() -> Self {
+        Outstanding {
+            count: AtomicUsize::new(0),
+            #[cfg(feature = "leak-backtraces")]
+            backtraces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[cfg_attr(not(feature = "leak-backtraces"), allow(unused_variables))]
+    pub fn acquire(&self, addr: usize) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "leak-backtraces")]
+        self.backtraces
+            .lock()
+            .unwrap()
+            .insert(addr, Backtrace::force_capture());
+    }
+
+    #[cfg_attr(not(feature = "leak-backtraces"), allow(unused_variables))]
+    pub fn release(&self, addr: usize) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+
+        #[cfg(feature = "leak-backtraces")]
+        self.backtraces.lock().unwrap().remove(&addr);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Describes the outstanding handles, or returns None if there are none.
+    pub fn report(&self, type_name: &str, label: Option<&str>) -> Option<String> {
+        let count = self.count();
+
+        if count == 0 {
+            return None;
+        }
+
+        let mut report = format!("FreeList<{}>", type_name);
+        if let Some(label) = label {
+            let _ = write!(report, " {:?}", label);
+        }
+        let _ = write!(report, " has {} outstanding Reuse handle(s)", count);
+
+        #[cfg(feature = "leak-backtraces")]
+        for (addr, backtrace) in self.backtraces.lock().unwrap().iter() {
+            let _ = write!(
+                report,
+                "\n\nReuse of {:#x} created at:\n{}",
+                addr, backtrace
+            );
+        }
+
+        Some(report)
+    }
+}
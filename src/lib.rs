@@ -60,14 +60,60 @@
 //! }
 //! ```
 
+#[macro_use]
+mod macros;
+
+mod buffer_pool;
+mod collection_pool;
+#[cfg(feature = "debug-checks")]
+mod debug_checks;
+mod drain;
 mod dump;
+#[cfg(any(feature = "prometheus", feature = "json"))]
+pub mod export;
 mod free_list;
+mod free_list_alloc;
+mod future;
+mod idle_trimmer;
+#[cfg(feature = "latency")]
+mod latency;
+#[cfg(feature = "leak-tracking")]
+mod leak;
+mod memory_budget;
+mod overflow;
+mod pool;
+mod quarantine;
+mod raw_block_pool;
+mod reclaimer;
+#[cfg(feature = "registry")]
+pub mod registry;
+mod replenisher;
 mod reusable;
 mod reuse;
 mod smart_pointer;
+#[cfg(feature = "stats")]
+mod stats;
+mod waiters;
+mod worker;
 
+pub use buffer_pool::{BufferPool, PooledBuf, SharedBuf};
+pub use collection_pool::{Collection, CollectionPool, Pooled, StringPool, VecPool};
+pub use drain::Drain;
 pub use free_list::FreeList;
+pub use free_list_alloc::FreeListAlloc;
+pub use future::{GetFuture, ReuseFuture};
+pub use idle_trimmer::IdleTrimmer;
+#[cfg(feature = "latency")]
+pub use latency::{Latencies, Latency};
+pub use memory_budget::MemoryBudget;
+pub use overflow::OverflowPolicy;
+pub use pool::Pool;
+pub use raw_block_pool::RawBlockPool;
+pub use reclaimer::Reclaimer;
+pub use replenisher::Replenisher;
 pub use reusable::Reusable;
 pub use reusable_derive::Reusable;
 pub use reuse::Reuse;
 pub use smart_pointer::SmartPointer;
+#[cfg(feature = "stats")]
+pub use stats::Stats;
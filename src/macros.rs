@@ -0,0 +1,54 @@
+/// Increments a counter of the `stats` feature in a [Dump](crate::dump::Dump).
+/// Expands to nothing when the feature is disabled.
+macro_rules! record_stat {
+    ($dump: expr, $counter: ident) => {
+        record_stat!($dump, $counter, 1)
+    };
+    ($dump: expr, $counter: ident, $n: expr) => {
+        #[cfg(feature = "stats")]
+        {
+            $dump
+                .counters
+                .shard()
+                .$counter
+                .fetch_add($n, std::sync::atomic::Ordering::Relaxed);
+        }
+    };
+}
+
+/// Emits a `tracing` event about a [FreeList](crate::FreeList), with its
+/// label and the type it holds as fields along with the given ones.
+/// Expands to nothing when the `tracing` feature is disabled.
+///
+/// The fields are only evaluated if a subscriber is interested in the event.
+macro_rules! trace_event {
+    ($free_list: expr, $level: ident, $message: literal $(, $field: ident = $value: expr)*) => {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::event!(
+                target: "lock_free_freelist",
+                tracing::Level::$level,
+                label = $free_list.label().unwrap_or(""),
+                type_name = $free_list.type_name(),
+                $($field = $value,)*
+                $message
+            );
+        }
+    };
+}
+
+/// Evaluates `$body` and, with the `latency` feature, records how long it
+/// took in the histogram `$histogram` of a [FreeList](crate::FreeList).
+macro_rules! timed {
+    ($free_list: expr, $histogram: ident, $body: expr) => {{
+        #[cfg(feature = "latency")]
+        let start = std::time::Instant::now();
+
+        let result = $body;
+
+        #[cfg(feature = "latency")]
+        $free_list.histograms.$histogram.record(start.elapsed());
+
+        result
+    }};
+}
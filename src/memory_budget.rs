@@ -0,0 +1,106 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A cap on the bytes retained by any number of free lists.
+///
+/// A [FreeList](crate::FreeList) registered with
+/// [FreeList::with_memory_budget](crate::FreeList::with_memory_budget)
+/// charges each pointer against the budget when it is thrown into the free list
+/// and gives the bytes back when the pointer is taken out.
+/// Once the budget is exhausted, returned pointers are dropped instead of kept.
+///
+/// Cloning a `MemoryBudget` gives another handle to the same budget.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::{FreeList, MemoryBudget};
+///
+/// let budget = MemoryBudget::new(1024);
+///
+/// let strings = FreeList::<Box<String>>::new()
+///     .with_memory_budget_sized(budget.clone(), |string| string.capacity());
+/// let numbers = FreeList::<Box<u64>>::new().with_memory_budget(budget.clone());
+///
+/// drop(strings.alloc(String::with_capacity(1000)));
+/// drop(numbers.alloc(5));
+///
+/// assert_eq!(budget.used(), 1008);
+///
+/// // no room left for this one, it is dropped
+/// drop(strings.alloc(String::with_capacity(100)));
+/// assert_eq!(strings.len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct MemoryBudget {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    limit: AtomicUsize,
+    used: AtomicUsize,
+}
+
+impl MemoryBudget {
+    /// Initialize a budget of `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        MemoryBudget {
+            inner: Arc::new(Inner {
+                limit: AtomicUsize::new(limit),
+                used: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Returns the number of bytes the free lists may retain.
+    pub fn limit(&self) -> usize {
+        self.inner.limit.load(Ordering::Relaxed)
+    }
+
+    /// Changes the limit. Lowering it below what is used doesn't
+    /// drop anything, it only stops more from being retained.
+    pub fn set_limit(&self, limit: usize) {
+        self.inner.limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// Returns the number of bytes currently retained by the free lists.
+    pub fn used(&self) -> usize {
+        self.inner.used.load(Ordering::Relaxed)
+    }
+
+    /// Charges `bytes` if they fit within the limit.
+    pub(crate) fn try_charge(&self, bytes: usize) -> bool {
+        let mut old_used = self.inner.used.load(Ordering::Relaxed);
+
+        loop {
+            let new_used = match old_used.checked_add(bytes) {
+                Some(new_used) if new_used <= self.limit() => new_used,
+                _ => return false,
+            };
+
+            match self.inner.used.compare_exchange_weak(
+                old_used,
+                new_used,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(old) => old_used = old,
+            };
+        }
+    }
+
+    /// Gives back `bytes` charged earlier.
+    pub(crate) fn refund(&self, bytes: usize) {
+        self.inner.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+/// The budget of a [FreeList](crate::FreeList) along with the function
+/// that tells how many bytes a pointer's contents retain.
+pub(crate) struct Charge<C> {
+    pub budget: MemoryBudget,
+    pub size_of: Box<dyn Fn(&C) -> usize + Send + Sync>,
+}
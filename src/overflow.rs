@@ -0,0 +1,78 @@
+use super::{free_list::FreeList, reusable::Reusable, smart_pointer::SmartPointer};
+use std::{
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+/// Decides what a [FreeList](crate::FreeList) does with a pointer
+/// when a [Reuse](crate::Reuse) is dropped but the free list is already full.
+///
+/// [OverflowPolicy::Drop] keeps the memory held by a free list bounded,
+/// the others trade memory for fewer allocations.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::{FreeList, OverflowPolicy};
+///
+/// let free_list = FreeList::<Box<i32>>::new().with_overflow_policy(OverflowPolicy::Spill);
+///
+/// // 100 is more than a free list can hold, the rest are spilled
+/// let boxes = (0..100).map(|i| free_list.alloc(i)).collect::<Vec<_>>();
+/// drop(boxes);
+///
+/// for i in 0..100 {
+///     assert!(free_list.reuse(i).is_ok());
+/// }
+/// ```
+#[derive(Default)]
+pub enum OverflowPolicy<T: SmartPointer>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    /// Drop the smart pointer. This is the default.
+    ///
+    /// If the free list has a [Reclaimer](crate::Reclaimer),
+    /// it is dropped on the reclaimer thread.
+    #[default]
+    Drop,
+    /// Push the pointer to an unbounded mutex protected overflow list.
+    /// [FreeList::reuse](crate::FreeList::reuse) takes pointers from there
+    /// when the free list itself is empty.
+    Spill,
+    /// Throw the pointer into another free list.
+    /// If that one is full as well, its own policy is applied.
+    ///
+    /// Two free lists forwarding to each other will recurse forever when both are full.
+    Forward(Arc<FreeList<T>>),
+    /// Hand the smart pointer over to a closure.
+    Callback(Box<dyn Fn(T) + Send + Sync>),
+}
+
+/// Storage for [OverflowPolicy::Spill].
+pub(crate) struct Spill<T> {
+    spilled: Mutex<Vec<*mut T>>,
+}
+
+unsafe impl<T> Send for Spill<T> {}
+unsafe impl<T> Sync for Spill<T> {}
+
+impl<T> Spill<T> {
+    pub fn new() -> Self {
+        Spill {
+            spilled: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn push(&self, raw: *mut T) {
+        self.spilled.lock().unwrap().push(raw);
+    }
+
+    pub fn pop(&self) -> Option<*mut T> {
+        self.spilled.lock().unwrap().pop()
+    }
+
+    pub fn take_all(&self) -> Vec<*mut T> {
+        std::mem::take(&mut *self.spilled.lock().unwrap())
+    }
+}
@@ -0,0 +1,235 @@
+use super::{
+    free_list::FreeList, future::GetFuture, reusable::Reusable, reuse::Reuse,
+    smart_pointer::SmartPointer,
+};
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+type Factory<T> = Box<dyn Fn() -> T + Send + Sync>;
+type Reset<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+/// An object pool built on a [FreeList](crate::FreeList).
+///
+/// Unlike [FreeList::reuse](crate::FreeList::reuse), [get](crate::Pool::get)
+/// doesn't take new contents. A recycled object is handed out as it is,
+/// after passing it through the optional reset closure, and a new one is
+/// created by the factory only when the free list is empty.
+/// This suits types that are expensive to construct but cheap to reset.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::Pool;
+///
+/// let pool = Pool::<Box<Vec<u8>>>::new(|| Vec::with_capacity(4096))
+///     .with_reset(|buf| buf.clear());
+///
+/// {
+///     let mut buf = pool.get();
+///     buf.extend_from_slice(b"hello");
+/// }
+///
+/// // the same buffer, cleared but with its capacity
+/// let buf = pool.get();
+/// assert!(buf.is_empty());
+/// assert!(buf.capacity() >= 4096);
+/// ```
+///
+/// # Bounded pools
+///
+/// A limit set by [with_limit](crate::Pool::with_limit) caps the number of
+/// live objects, counting both the pooled ones and the ones in use.
+/// Once it is reached, objects are only handed out when one is returned
+/// to the pool or dropped, see [try_get](crate::Pool::try_get)
+/// and [get_blocking](crate::Pool::get_blocking).
+pub struct Pool<T: SmartPointer>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    free_list: FreeList<T>,
+    factory: Factory<<T as Deref>::Target>,
+    reset: Option<Reset<<T as Deref>::Target>>,
+    limit: AtomicUsize,
+}
+
+impl<T: SmartPointer> Pool<T>
+where
+    <T as Deref>::Target: Sized + Reusable,
+{
+    /// Initialize an empty pool which creates objects using `factory`.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::Pool;
+    ///
+    /// let pool = Pool::<Box<String>>::new(String::new);
+    /// ```
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: Fn() -> <T as Deref>::Target + Send + Sync + 'static,
+    {
+        Pool {
+            free_list: FreeList::new(),
+            factory: Box::new(factory),
+            reset: None,
+            limit: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    /// Sets a closure that is called on a recycled object
+    /// before [get](crate::Pool::get) hands it out.
+    pub fn with_reset<R>(mut self, reset: R) -> Self
+    where
+        R: Fn(&mut <T as Deref>::Target) + Send + Sync + 'static,
+    {
+        self.reset = Some(Box::new(reset));
+        self
+    }
+
+    /// Replaces the underlying free list, e.g. with one that has
+    /// an [OverflowPolicy](crate::OverflowPolicy) or a [Reclaimer](crate::Reclaimer).
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::{FreeList, OverflowPolicy, Pool};
+    ///
+    /// let pool = Pool::<Box<String>>::new(String::new)
+    ///     .with_free_list(FreeList::new().with_overflow_policy(OverflowPolicy::Spill));
+    /// ```
+    pub fn with_free_list(mut self, free_list: FreeList<T>) -> Self {
+        self.free_list = free_list;
+        self
+    }
+
+    /// Caps the number of live objects of this pool to `limit`.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::Pool;
+    ///
+    /// let pool = Pool::<Box<String>>::new(String::new).with_limit(1);
+    ///
+    /// let first = pool.try_get().unwrap();
+    /// assert!(pool.try_get().is_none());
+    ///
+    /// drop(first);
+    /// assert!(pool.try_get().is_some());
+    /// ```
+    pub fn with_limit(self, limit: usize) -> Self {
+        self.set_limit(limit);
+        self
+    }
+
+    /// Changes the limit on live objects, waking up threads blocked in
+    /// [get_blocking](crate::Pool::get_blocking) if it was raised.
+    ///
+    /// Lowering it below the number of live objects doesn't drop any of them,
+    /// it only stops new ones from being created.
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed);
+        self.free_list.waiters.notify_all();
+    }
+
+    /// Returns the limit on live objects, `usize::MAX` if the pool is unbounded.
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of live objects of this pool,
+    /// whether they are pooled or in use.
+    pub fn live(&self) -> usize {
+        self.free_list.live.load(Ordering::Relaxed)
+    }
+
+    /// Returns a recycled object if there is one,
+    /// otherwise creates a new one by calling the factory.
+    ///
+    /// If the pool is bounded and at its limit, this blocks until
+    /// an object is available. See [get_blocking](crate::Pool::get_blocking).
+    pub fn get(&self) -> Reuse<'_, T> {
+        self.free_list
+            .waiters
+            .wait_until(None, || self.try_get())
+            .unwrap()
+    }
+
+    /// Like [get](crate::Pool::get) but returns `None` instead of
+    /// blocking if the pool is at its limit.
+    pub fn try_get(&self) -> Option<Reuse<'_, T>> {
+        if let Some(ptr) = self.free_list.recycle() {
+            let mut recycled = unsafe { T::from_raw(ptr) };
+
+            if let Some(reset) = &self.reset {
+                reset(&mut recycled);
+            }
+
+            return Some(Reuse::from_live(recycled, &self.free_list));
+        }
+
+        // Reserve a place for the new object within the limit
+        let mut old_live = self.free_list.live.load(Ordering::Relaxed);
+
+        loop {
+            if old_live >= self.limit.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            match self.free_list.live.compare_exchange_weak(
+                old_live,
+                old_live + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(old) => old_live = old,
+            };
+        }
+
+        record_stat!(self.free_list.dump, allocs);
+
+        let allocated = T::new((self.factory)());
+
+        #[cfg(feature = "debug-checks")]
+        self.free_list.tags.insert(&*allocated);
+
+        Some(Reuse::from_live(allocated, &self.free_list))
+    }
+
+    /// Like [get](crate::Pool::get) but gives up and
+    /// returns `None` if no object is available within `timeout`.
+    /// A timeout too long to be represented, like `Duration::MAX`,
+    /// waits forever.
+    ///
+    /// # Example
+    /// ```
+    /// use lock_free_freelist::Pool;
+    /// use std::time::Duration;
+    ///
+    /// let pool = Pool::<Box<String>>::new(String::new).with_limit(1);
+    ///
+    /// let first = pool.get();
+    /// assert!(pool.get_blocking(Duration::from_millis(10)).is_none());
+    /// ```
+    pub fn get_blocking(&self, timeout: Duration) -> Option<Reuse<'_, T>> {
+        let deadline = Instant::now().checked_add(timeout);
+
+        self.free_list
+            .waiters
+            .wait_until(deadline, || self.try_get())
+    }
+
+    /// Async version of [get](crate::Pool::get). The returned future
+    /// completes once an object is available and doesn't depend
+    /// on any particular async runtime.
+    pub fn get_async(&self) -> GetFuture<'_, T> {
+        GetFuture::new(self)
+    }
+
+    /// Returns the free list holding the pooled objects.
+    pub fn free_list(&self) -> &FreeList<T> {
+        &self.free_list
+    }
+}
@@ -0,0 +1,66 @@
+use std::{collections::VecDeque, mem::MaybeUninit, ptr, sync::Mutex};
+
+/// Closure that overwrites the contents of a returned pointer,
+/// see [FreeList::with_poison](crate::FreeList::with_poison).
+pub(crate) type Poison<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+/// Pointers returned to a [FreeList](crate::FreeList) but not yet
+/// eligible for reuse, oldest first.
+///
+/// Their contents are dropped on the way in and the memory is left
+/// uninitialized until they are released with `fresh` contents.
+pub(crate) struct Quarantine<T> {
+    len: usize,
+    held: Mutex<VecDeque<*mut T>>,
+    fresh: fn() -> T,
+}
+
+unsafe impl<T> Send for Quarantine<T> {}
+unsafe impl<T> Sync for Quarantine<T> {}
+
+impl<T> Quarantine<T> {
+    pub fn new(len: usize, fresh: fn() -> T) -> Self {
+        Quarantine {
+            len,
+            held: Mutex::new(VecDeque::new()),
+            fresh,
+        }
+    }
+
+    /// Drops the contents of `raw` and puts it in quarantine, then returns
+    /// the oldest pointer with fresh contents if that makes more than `len` of them.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must point to initialized contents that nothing else uses anymore.
+    pub unsafe fn push(&self, raw: *mut T) -> Option<*mut T> {
+        ptr::drop_in_place(raw);
+        // Reads through a stale pointer are now reads of uninitialized memory
+        raw.cast::<MaybeUninit<T>>().write(MaybeUninit::uninit());
+
+        let released = {
+            let mut held = self.held.lock().unwrap();
+
+            held.push_back(raw);
+
+            if held.len() > self.len {
+                held.pop_front()
+            } else {
+                None
+            }
+        };
+
+        released.map(|raw| self.refill(raw))
+    }
+
+    /// Releases the oldest pointer with fresh contents.
+    pub fn pop(&self) -> Option<*mut T> {
+        let raw = self.held.lock().unwrap().pop_front()?;
+        Some(self.refill(raw))
+    }
+
+    fn refill(&self, raw: *mut T) -> *mut T {
+        unsafe { raw.write((self.fresh)()) };
+        raw
+    }
+}
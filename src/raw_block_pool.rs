@@ -0,0 +1,127 @@
+use super::dump::Dump;
+use std::{
+    alloc::{self, Layout},
+    fmt,
+    ptr::NonNull,
+};
+
+/// A pool of untyped memory blocks of one [Layout].
+///
+/// [allocate](crate::RawBlockPool::allocate) reuses a block given back by
+/// [deallocate](crate::RawBlockPool::deallocate) if there is one and
+/// otherwise gets a new one from [std::alloc]. Freed blocks are kept
+/// in a lock free dump like the one inside a [FreeList](crate::FreeList),
+/// so it holds as many as there are bits in `usize`. The rest are
+/// deallocated right away, and the ones kept are deallocated when
+/// the pool is dropped.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::RawBlockPool;
+/// use std::alloc::Layout;
+///
+/// let pool = RawBlockPool::new(Layout::from_size_align(256, 16).unwrap());
+///
+/// let block = pool.allocate();
+/// assert_eq!(block.as_ptr() as usize % 16, 0);
+///
+/// unsafe {
+///     block.as_ptr().write_bytes(0, 256);
+///     pool.deallocate(block);
+/// }
+///
+/// assert_eq!(pool.allocate(), block);
+/// ```
+pub struct RawBlockPool {
+    layout: Layout,
+    dump: Dump<u8>,
+}
+
+impl RawBlockPool {
+    /// Returns an empty pool of blocks of `layout`.
+    ///
+    /// Blocks of a zero sized layout are dangling pointers
+    /// that are never allocated.
+    pub fn new(layout: Layout) -> Self {
+        RawBlockPool {
+            layout,
+            dump: Dump::new(),
+        }
+    }
+
+    /// Returns the layout of the blocks.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns a block of memory of the pool's layout.
+    /// Its contents are uninitialized.
+    ///
+    /// Calls [handle_alloc_error](std::alloc::handle_alloc_error)
+    /// if the allocation fails.
+    pub fn allocate(&self) -> NonNull<u8> {
+        if self.layout.size() == 0 {
+            return unsafe { NonNull::new_unchecked(self.layout.align() as *mut u8) };
+        }
+
+        if let Ok(block) = self.dump.recycle() {
+            return unsafe { NonNull::new_unchecked(block) };
+        }
+
+        match NonNull::new(unsafe { alloc::alloc(self.layout) }) {
+            Some(block) => block,
+            None => alloc::handle_alloc_error(self.layout),
+        }
+    }
+
+    /// Gives a block back to the pool.
+    ///
+    /// # Safety
+    ///
+    /// `block` must have been returned by [allocate](crate::RawBlockPool::allocate)
+    /// of this pool and must not be used after this.
+    pub unsafe fn deallocate(&self, block: NonNull<u8>) {
+        if self.layout.size() == 0 {
+            return;
+        }
+
+        if let Err(block) = self.dump.throw(block.as_ptr()) {
+            alloc::dealloc(block, self.layout);
+        }
+    }
+
+    /// Returns the number of free blocks kept in the pool.
+    ///
+    /// Only approximate if other threads are using the pool.
+    pub fn len(&self) -> usize {
+        self.dump.len()
+    }
+
+    /// Returns true if there are no free blocks in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Deallocates the free blocks. Blocks still allocated
+/// from the pool are not affected and leak unless
+/// deallocated with [std::alloc::dealloc] and the pool's layout.
+impl Drop for RawBlockPool {
+    fn drop(&mut self) {
+        let layout = self.layout;
+
+        unsafe {
+            self.dump.for_each(|block| alloc::dealloc(block, layout));
+        }
+    }
+}
+
+impl fmt::Debug for RawBlockPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawBlockPool")
+            .field("layout", &self.layout)
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::{self, JoinHandle, Thread},
+};
+
+/// A background thread that drops the values handed to it,
+/// keeping `free()` and destructors off the threads that retire them.
+///
+/// Values are passed to the thread through a lock free stack,
+/// so [retire](crate::Reclaimer::retire) never blocks.
+///
+/// A [FreeList](crate::FreeList) uses it through
+/// [FreeList::with_reclaimer](crate::FreeList::with_reclaimer).
+///
+/// A value whose destructor panics is given up on, the thread
+/// keeps dropping the rest.
+///
+/// Cloning a `Reclaimer` gives another handle to the same thread.
+/// When the last handle is dropped, the remaining values are dropped
+/// and the thread exits.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::Reclaimer;
+///
+/// let reclaimer = Reclaimer::new();
+///
+/// reclaimer.retire(vec![0u8; 1024]);
+///
+/// // wait for the vector to be dropped
+/// reclaimer.flush();
+/// ```
+pub struct Reclaimer {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    head: AtomicPtr<Node>,
+    /// Number of values retired so far.
+    retired: AtomicU64,
+    /// Number of values dropped so far.
+    reclaimed: AtomicU64,
+    /// Number of `Reclaimer` handles.
+    handles: AtomicUsize,
+    shutdown: AtomicBool,
+    worker: Mutex<Option<JoinHandle<()>>>,
+    worker_thread: Thread,
+    flushed: (Mutex<()>, Condvar),
+}
+
+struct Node {
+    garbage: Box<dyn Send>,
+    next: *mut Node,
+}
+
+impl Default for Reclaimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reclaimer {
+    /// Spawns the reclaimer thread.
+    pub fn new() -> Self {
+        let (tx, rx) = std::sync::mpsc::channel::<Arc<Inner>>();
+
+        let worker = thread::Builder::new()
+            .name("freelist-reclaimer".to_string())
+            .spawn(move || {
+                let inner = rx.recv().unwrap();
+                inner.run();
+            })
+            .unwrap();
+
+        let inner = Arc::new(Inner {
+            head: AtomicPtr::new(null_mut()),
+            retired: AtomicU64::new(0),
+            reclaimed: AtomicU64::new(0),
+            handles: AtomicUsize::new(1),
+            shutdown: AtomicBool::new(false),
+            worker_thread: worker.thread().clone(),
+            worker: Mutex::new(Some(worker)),
+            flushed: (Mutex::new(()), Condvar::new()),
+        });
+
+        tx.send(Arc::clone(&inner)).unwrap();
+
+        Reclaimer { inner }
+    }
+
+    /// Sends `garbage` to the reclaimer thread to be dropped there.
+    pub fn retire<G: Send + 'static>(&self, garbage: G) {
+        let node = Box::into_raw(Box::new(Node {
+            garbage: Box::new(garbage),
+            next: null_mut(),
+        }));
+
+        self.inner.retired.fetch_add(1, Ordering::Relaxed);
+
+        let mut old_head = self.inner.head.load(Ordering::Relaxed);
+
+        loop {
+            unsafe {
+                (*node).next = old_head;
+            }
+
+            match self.inner.head.compare_exchange_weak(
+                old_head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(old) => old_head = old,
+            };
+        }
+
+        self.inner.worker_thread.unpark();
+    }
+
+    /// Blocks until every value retired so far has been dropped.
+    ///
+    /// Values retired while it waits don't keep it waiting longer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called on the reclaimer thread, e.g. from the destructor
+    /// of a retired value, as it would wait for itself.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lock_free_freelist::Reclaimer;
+    /// use std::sync::Arc;
+    ///
+    /// let reclaimer = Reclaimer::new();
+    /// let shared = Arc::new(5);
+    ///
+    /// reclaimer.retire(Arc::clone(&shared));
+    /// reclaimer.flush();
+    ///
+    /// assert_eq!(Arc::strong_count(&shared), 1);
+    /// ```
+    pub fn flush(&self) {
+        assert!(
+            thread::current().id() != self.inner.worker_thread.id(),
+            "Reclaimer::flush called on the reclaimer thread"
+        );
+
+        /*
+         * Every value whose `retire()` returned before this load is counted in it.
+         * The reclaimer thread takes the whole stack at once, so the values
+         * it dropped before getting to one of those were pushed before it
+         * and are counted too. `reclaimed` can only reach `retired`
+         * once all of them are dropped.
+         */
+        let retired = self.inner.retired.load(Ordering::Relaxed);
+
+        let (lock, condvar) = &self.inner.flushed;
+        let mut guard = lock.lock().unwrap();
+
+        while self.inner.reclaimed.load(Ordering::Acquire) < retired {
+            guard = condvar.wait(guard).unwrap();
+        }
+    }
+}
+
+impl Clone for Reclaimer {
+    fn clone(&self) -> Self {
+        self.inner.handles.fetch_add(1, Ordering::Relaxed);
+
+        Reclaimer {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+/// The last handle to be dropped stops the reclaimer thread
+/// after it has dropped everything retired to it.
+///
+/// If that happens on the reclaimer thread itself, because a retired
+/// value held the last handle, the thread is left to exit on its own.
+impl Drop for Reclaimer {
+    fn drop(&mut self) {
+        if self.inner.handles.fetch_sub(1, Ordering::AcqRel) != 1 {
+            return;
+        }
+
+        self.inner.shutdown.store(true, Ordering::Release);
+        self.inner.worker_thread.unpark();
+
+        let worker = self.inner.worker.lock().unwrap().take();
+
+        // It can't wait for itself
+        if thread::current().id() == self.inner.worker_thread.id() {
+            return;
+        }
+
+        if let Some(worker) = worker {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Inner {
+    fn run(&self) {
+        loop {
+            let mut node = self.head.swap(null_mut(), Ordering::Acquire);
+
+            if node.is_null() {
+                if self.shutdown.load(Ordering::Acquire) {
+                    // Values retired before the last handle was dropped
+                    // are visible now, drop them before exiting.
+                    if self.head.load(Ordering::Acquire).is_null() {
+                        break;
+                    }
+                } else {
+                    thread::park();
+                }
+                continue;
+            }
+
+            let mut reclaimed = 0;
+
+            while !node.is_null() {
+                let Node { garbage, next } = *unsafe { Box::from_raw(node) };
+                node = next;
+                // A panicking destructor must not take the thread down,
+                // the value counts as reclaimed either way.
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(garbage)));
+                reclaimed += 1;
+            }
+
+            self.reclaimed.fetch_add(reclaimed, Ordering::Release);
+
+            let (lock, condvar) = &self.flushed;
+            let _guard = lock.lock().unwrap();
+            condvar.notify_all();
+        }
+    }
+}
+
+/// The reclaimer of a [FreeList](crate::FreeList) along with
+/// functions to retire its smart pointers and their contents.
+///
+/// The functions are instantiated by
+/// [FreeList::with_reclaimer](crate::FreeList::with_reclaimer)
+/// which is where the `Send + 'static` bounds are known to hold.
+pub(crate) struct Deferred<P, C> {
+    pub reclaimer: Reclaimer,
+    pub retire_pointer: fn(&Reclaimer, P),
+    pub retire_contents: fn(&Reclaimer, C),
+}
@@ -0,0 +1,108 @@
+//! A process wide registry of free lists, for diagnostics.
+//!
+//! With the `registry` feature, every [FreeList](crate::FreeList) registers
+//! itself when it is created and unregisters when it is dropped.
+//! [snapshot] lists the free lists alive at the moment along with their
+//! state, e.g. for logging or an admin endpoint.
+//!
+//! # Example
+//!
+//! ```
+//! use lock_free_freelist::{registry, FreeList};
+//!
+//! let free_list = FreeList::<Box<String>>::new().with_label("names");
+//! drop(free_list.alloc("Jane".to_string()));
+//!
+//! for pool in registry::snapshot() {
+//!     println!(
+//!         "{} {:?}: {}/{}",
+//!         pool.type_name, pool.label, pool.len, pool.capacity
+//!     );
+//! }
+//! ```
+
+use super::{dump::Dump, stats::Stats};
+use std::sync::{Mutex, MutexGuard};
+
+/// State of a registered free list at the time of [snapshot].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    /// [type_name](std::any::type_name) of the type the free list holds.
+    pub type_name: &'static str,
+    /// Label given by [FreeList::with_label](crate::FreeList::with_label).
+    pub label: Option<String>,
+    /// Number of free pointers in the free list.
+    pub len: usize,
+    /// Maximum number of free pointers the free list can hold.
+    pub capacity: usize,
+    /// Counters of the free list.
+    pub stats: Stats,
+}
+
+struct Entry {
+    /// The `Dump<T>` of the free list. Entries are removed before
+    /// the dump is dropped, so it is valid as long as the registry is locked.
+    dump: *const (),
+    type_name: &'static str,
+    label: Option<String>,
+    snapshot: unsafe fn(*const ()) -> (usize, usize, Stats),
+}
+
+unsafe impl Send for Entry {}
+
+static REGISTRY: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+fn lock() -> MutexGuard<'static, Vec<Entry>> {
+    // Entries are consistent even if a thread panicked with the lock held
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+unsafe fn snapshot_of<T>(dump: *const ()) -> (usize, usize, Stats) {
+    let dump = &*(dump as *const Dump<T>);
+    let len = dump.len();
+
+    (len, dump.capacity(), dump.counters.snapshot(len))
+}
+
+/// Returns the state of every free list alive at the moment.
+pub fn snapshot() -> Vec<PoolSnapshot> {
+    lock()
+        .iter()
+        .map(|entry| {
+            let (len, capacity, stats) = unsafe { (entry.snapshot)(entry.dump) };
+
+            PoolSnapshot {
+                type_name: entry.type_name,
+                label: entry.label.clone(),
+                len,
+                capacity,
+                stats,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn register<T>(dump: &Dump<T>, type_name: &'static str) {
+    lock().push(Entry {
+        dump: dump as *const Dump<T> as *const (),
+        type_name,
+        label: None,
+        snapshot: snapshot_of::<T>,
+    });
+}
+
+pub(crate) fn set_label<T>(dump: &Dump<T>, label: &str) {
+    let dump = dump as *const Dump<T> as *const ();
+
+    if let Some(entry) = lock().iter_mut().find(|entry| entry.dump == dump) {
+        entry.label = Some(label.to_string());
+    }
+}
+
+pub(crate) fn unregister<T>(dump: &Dump<T>) {
+    let dump = dump as *const Dump<T> as *const ();
+
+    lock().retain(|entry| entry.dump != dump);
+}
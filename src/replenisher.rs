@@ -0,0 +1,65 @@
+use super::{
+    free_list::FreeList, reusable::Reusable, smart_pointer::SmartPointer, worker::Periodic,
+};
+use std::{ops::Deref, time::Duration};
+
+/// A background thread that keeps at least `low_watermark` free pointers
+/// in a [FreeList](crate::FreeList), so that a burst of
+/// [reuse_or_alloc](crate::FreeList::reuse_or_alloc) calls doesn't fall back to allocation.
+///
+/// Every `interval`, it tops the free list up using
+/// [FreeList::prefill](crate::FreeList::prefill).
+/// The free list can be given as an [Arc](std::sync::Arc) or a `&'static` reference.
+///
+/// The thread is stopped when the `Replenisher` is dropped.
+///
+/// # Example
+///
+/// ```
+/// use lock_free_freelist::{FreeList, Replenisher};
+/// use std::{sync::Arc, time::Duration};
+///
+/// let free_list = Arc::new(FreeList::<Box<String>>::new());
+///
+/// let replenisher = Replenisher::spawn(
+///     Arc::clone(&free_list),
+///     16,
+///     Duration::from_millis(1),
+///     || String::with_capacity(256),
+/// );
+///
+/// // pointers will be available to reuse shortly
+/// let reused = free_list.reuse_or_alloc("hello".to_string());
+/// ```
+pub struct Replenisher {
+    _worker: Periodic,
+}
+
+impl Replenisher {
+    /// Spawns the replenisher thread for `free_list`.
+    ///
+    /// Objects are made on that thread and handed out on others,
+    /// hence the `Send` bounds.
+    pub fn spawn<L, T, F>(
+        free_list: L,
+        low_watermark: usize,
+        interval: Duration,
+        mut factory: F,
+    ) -> Self
+    where
+        L: Deref<Target = FreeList<T>> + Send + 'static,
+        T: SmartPointer + Send,
+        <T as Deref>::Target: Sized + Reusable + Send,
+        F: FnMut() -> <T as Deref>::Target + Send + 'static,
+    {
+        let worker = Periodic::spawn("freelist-replenisher", interval, move || {
+            let len = free_list.len();
+
+            if len < low_watermark {
+                free_list.prefill(low_watermark - len, &mut factory);
+            }
+        });
+
+        Replenisher { _worker: worker }
+    }
+}
@@ -38,4 +38,5 @@ macro_rules! impl_generic_reusable {
 impl_reusable!(u8, i8, u16, i16, i32, u32, i64, u64, i128, u128);
 impl_reusable!(String);
 impl_generic_reusable!(Option<T>, T);
+impl_generic_reusable!(Vec<T>, T);
 impl_generic_reusable!(Result<T, E>, T, E);
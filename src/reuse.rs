@@ -2,6 +2,7 @@ use super::{free_list::FreeList, reusable::Reusable, smart_pointer::SmartPointer
 use std::{
     mem::ManuallyDrop,
     ops::{Deref, DerefMut},
+    sync::atomic::Ordering,
 };
 
 /// This is a wrapper around smart pointers so that
@@ -43,10 +44,27 @@ where
     <T as Deref>::Target: Sized + Reusable,
 {
     /// Get a new [Reuse](crate::Reuse) instance.
+    ///
+    /// `smart_pointer` is counted among the live pointers of `free_list`
+    /// from here on, see [Pool::live](crate::Pool::live).
     pub fn new<'b>(smart_pointer: T, free_list: &'b FreeList<T>) -> Reuse<'b, T> {
+        free_list.live.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        free_list.tags.insert(&*smart_pointer);
+        Reuse::from_live(smart_pointer, free_list)
+    }
+
+    /// Like [new](crate::Reuse::new) but for a pointer that
+    /// is already counted among the live pointers of `free_list`.
+    pub(crate) fn from_live<'b>(smart_pointer: T, free_list: &'b FreeList<T>) -> Reuse<'b, T> {
+        #[cfg(feature = "leak-tracking")]
+        free_list
+            .outstanding
+            .acquire(&*smart_pointer as *const _ as usize);
+
         Reuse {
             smart_pointer: ManuallyDrop::new(smart_pointer),
-            free_list: free_list,
+            free_list,
         }
     }
 }
@@ -74,7 +92,8 @@ where
 /// When the instance of this type is dropped,
 /// an attempt is made to put the pointer of the contained
 /// [SmartPointer](crate::SmartPointer) into free list
-/// and if free list is full, the contents are dropped.
+/// and if free list is full, it is handed to the
+/// [OverflowPolicy](crate::OverflowPolicy) of the free list.
 impl<'a, T: SmartPointer> Drop for Reuse<'a, T>
 where
     <T as Deref>::Target: Sized + Reusable,
@@ -84,15 +103,11 @@ where
 
         let garbage = T::into_raw(smart_pointer);
 
+        #[cfg(feature = "leak-tracking")]
+        self.free_list.outstanding.release(garbage as usize);
+
         // Try to add this memory to free list and if free list
-        // is full then drop it.
-        if let Err(ptr) = self.free_list.dump.throw(garbage) {
-            // We come here if the dump is full.
-            // Here we will have to drop the value instead
-            // of storing it in dump.
-            unsafe {
-                let _to_drop = T::from_raw(ptr);
-            }
-        }
+        // is full then let the overflow policy handle it.
+        timed!(self.free_list, drop, self.free_list.throw(garbage));
     }
 }
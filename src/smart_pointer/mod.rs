@@ -1,4 +1,5 @@
 mod r#box;
+#[allow(clippy::module_inception)]
 mod smart_pointer;
 
 pub use smart_pointer::SmartPointer;
@@ -12,11 +12,22 @@ use std::ops::{Deref, DerefMut};
 /// becuase the pointer could still be out there after the container is dropped.
 ///
 /// For this reason the trait is unsafe.
+///
+/// # Safety
+///
+/// `into_raw()` must give away the sole ownership of the pointee and
+/// `from_raw()` must take it back, so that a pointer can be stored in
+/// the free list and turned into an instance of `Self` again later.
 pub unsafe trait SmartPointer: Deref + DerefMut
 where
     <Self as Deref>::Target: Sized + Reusable,
 {
     /// Constructs an instance of Self by a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been returned by `into_raw()` of the same type
+    /// and must not be used to construct another instance.
     unsafe fn from_raw(raw: *mut <Self as Deref>::Target) -> Self;
 
     /// Consumes Self to return the contained raw pointer.
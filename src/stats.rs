@@ -0,0 +1,113 @@
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+/// A snapshot of the counters of a [FreeList](crate::FreeList),
+/// returned by [FreeList::stats](crate::FreeList::stats).
+///
+/// The counters are updated without synchronizing with each other,
+/// so a snapshot taken while the free list is in use is approximate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Pointers taken out of the free list to be reused.
+    pub reuses: u64,
+    /// Pointers newly allocated because there was nothing to reuse.
+    pub allocs: u64,
+    /// Pointers successfully thrown into the free list.
+    pub throws: u64,
+    /// Pointers that didn't fit in the free list and were handed
+    /// to its [OverflowPolicy](crate::OverflowPolicy).
+    pub overflows: u64,
+    /// Pointers rejected by the
+    /// [return filter](crate::FreeList::with_return_filter) and dropped.
+    pub rejected: u64,
+    /// Failed compare and swaps on the bitmaps, a measure of contention.
+    pub cas_retries: u64,
+    /// Number of pointers in the free list.
+    pub len: usize,
+    /// Highest number of pointers the free list has held at once.
+    pub peak_len: usize,
+}
+
+const SHARDS: usize = 16;
+
+/// Counters updated by a subset of the threads. Aligned
+/// to a cache line so that the shards don't contend with each other.
+#[repr(align(64))]
+pub(crate) struct Shard {
+    pub reuses: AtomicU64,
+    pub allocs: AtomicU64,
+    pub throws: AtomicU64,
+    pub overflows: AtomicU64,
+    pub rejected: AtomicU64,
+    pub cas_retries: AtomicU64,
+}
+
+/// Counters of a [Dump](crate::dump::Dump), sharded by thread.
+pub(crate) struct Counters {
+    shards: [Shard; SHARDS],
+    peak_len: AtomicUsize,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_SHARD: Shard = Shard {
+    reuses: AtomicU64::new(0),
+    allocs: AtomicU64::new(0),
+    throws: AtomicU64::new(0),
+    overflows: AtomicU64::new(0),
+    rejected: AtomicU64::new(0),
+    cas_retries: AtomicU64::new(0),
+};
+
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD_INDEX: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+impl Counters {
+    pub const fn new() -> Self {
+        Counters {
+            shards: [EMPTY_SHARD; SHARDS],
+            peak_len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the shard of the calling thread.
+    pub fn shard(&self) -> &Shard {
+        let index = SHARD_INDEX.with(|index| {
+            if index.get() == usize::MAX {
+                index.set(NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % SHARDS);
+            }
+            index.get()
+        });
+
+        &self.shards[index]
+    }
+
+    pub fn record_len(&self, len: usize) {
+        if len > self.peak_len.load(Ordering::Relaxed) {
+            self.peak_len.fetch_max(len, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self, len: usize) -> Stats {
+        let mut stats = Stats {
+            len,
+            peak_len: self.peak_len.load(Ordering::Relaxed),
+            ..Stats::default()
+        };
+
+        for shard in self.shards.iter() {
+            stats.reuses += shard.reuses.load(Ordering::Relaxed);
+            stats.allocs += shard.allocs.load(Ordering::Relaxed);
+            stats.throws += shard.throws.load(Ordering::Relaxed);
+            stats.overflows += shard.overflows.load(Ordering::Relaxed);
+            stats.rejected += shard.rejected.load(Ordering::Relaxed);
+            stats.cas_retries += shard.cas_retries.load(Ordering::Relaxed);
+        }
+
+        stats
+    }
+}
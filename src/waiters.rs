@@ -0,0 +1,478 @@
+use std::{
+    cell::UnsafeCell,
+    collections::VecDeque,
+    ptr::null_mut,
+    sync::{
+        atomic::{fence, AtomicBool, AtomicPtr, AtomicU8, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::Instant,
+};
+
+/// Threads and tasks waiting for a pointer to be returned to a free list.
+///
+/// The returning side only pays for two loads unless somebody is actually waiting.
+/// Then it wakes one blocked thread and one pending future per returned pointer.
+///
+/// Each pending future owns a [Slot], pushed onto a lock free stack
+/// when the future starts waiting. Wakeups are counted in `owed` and handed out
+/// by whichever thread manages to set `draining`. It moves the stack into `queue`,
+/// oldest first, and wakes as many queued slots as it owes. Threads that
+/// find `draining` set only add to `owed`, the draining thread looks again
+/// after clearing it, so nothing ever blocks on it.
+pub(crate) struct Waiters {
+    waiting: AtomicUsize,
+    lock: Mutex<()>,
+    condvar: Condvar,
+    /// Lock free stack of slots that started waiting, newest first.
+    incoming: AtomicPtr<Slot>,
+    /// Slots taken from `incoming`, oldest first.
+    /// Only used by the thread that set `draining`.
+    queue: UnsafeCell<VecDeque<Arc<Slot>>>,
+    draining: AtomicBool,
+    /// Wakeups to hand out to queued slots.
+    owed: AtomicUsize,
+    /// Number of slots waiting to be woken.
+    queued: AtomicUsize,
+    /// Number of slots in `incoming` and `queue` whose future is gone.
+    dead: AtomicUsize,
+}
+
+/// `queue` is only accessed by the thread that set `draining`.
+unsafe impl Send for Waiters {}
+unsafe impl Sync for Waiters {}
+
+/// Where a pending future waits, reused every time it is polled.
+#[derive(Default)]
+pub(crate) struct Registration {
+    slot: Option<Arc<Slot>>,
+}
+
+/// Not in `incoming` nor `queue`.
+const IDLE: u8 = 0;
+/// In `incoming` or `queue`, waiting to be woken.
+const QUEUED: u8 = 1;
+/// Taken out of `queue` and woken.
+const WOKEN: u8 = 2;
+/// The future is gone, dropped from `queue` when it gets there.
+const DEAD: u8 = 3;
+
+/// A pending future's place among the waiters.
+pub(crate) struct Slot {
+    state: AtomicU8,
+    waker: AtomicWaker,
+    next: AtomicPtr<Slot>,
+}
+
+impl Waiters {
+    pub fn new() -> Self {
+        Waiters {
+            waiting: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            condvar: Condvar::new(),
+            incoming: AtomicPtr::new(null_mut()),
+            queue: UnsafeCell::new(VecDeque::new()),
+            draining: AtomicBool::new(false),
+            owed: AtomicUsize::new(0),
+            queued: AtomicUsize::new(0),
+            dead: AtomicUsize::new(0),
+        }
+    }
+
+    /// Wakes up a waiting thread and a waiting task for the pointer
+    /// that was just made available.
+    ///
+    /// Must be called after the pointer is made available by
+    /// a `SeqCst` read-modify-write, like the one in `Dump::throw()`,
+    /// or under a lock that taking it also goes through.
+    /// Otherwise use [notify_fenced](Waiters::notify_fenced).
+    pub fn notify(&self) {
+        /*
+         * The `SeqCst` write that made the pointer available and these loads
+         * pair with the fence in `wait_until()` and `poll_take()`.
+         * Either the waiter sees the pointer made available before
+         * this call or this sees `waiting` or `queued` incremented.
+         * A blocked thread can only be notified once it is in `wait()`
+         * since it holds the lock until then.
+         */
+        if self.waiting.load(Ordering::SeqCst) != 0 {
+            let _guard = self.lock.lock().unwrap();
+            self.condvar.notify_one();
+        }
+
+        if self.queued.load(Ordering::SeqCst) != 0 {
+            self.owed.fetch_add(1, Ordering::SeqCst);
+            self.drain();
+        }
+    }
+
+    /// Same as [notify](Waiters::notify), for when something else made
+    /// room for a waiter, like a lower count of live pointers.
+    pub fn notify_fenced(&self) {
+        fence(Ordering::SeqCst);
+        self.notify();
+    }
+
+    /// Wakes up everybody, for when room was made for more than one waiter,
+    /// like a raised limit or a trim.
+    pub fn notify_all(&self) {
+        fence(Ordering::SeqCst);
+
+        if self.waiting.load(Ordering::SeqCst) != 0 {
+            let _guard = self.lock.lock().unwrap();
+            self.condvar.notify_all();
+        }
+
+        let queued = self.queued.load(Ordering::SeqCst);
+
+        if queued != 0 {
+            self.owed.fetch_add(queued, Ordering::SeqCst);
+            self.drain();
+        }
+    }
+
+    /// Calls `try_take` until it returns `Some`, waiting for
+    /// [notify](Waiters::notify) in between. Returns `None` if `deadline` passes first.
+    pub fn wait_until<R, F>(&self, deadline: Option<Instant>, mut try_take: F) -> Option<R>
+    where
+        F: FnMut() -> Option<R>,
+    {
+        if let Some(taken) = try_take() {
+            return Some(taken);
+        }
+
+        let mut guard = self.lock.lock().unwrap();
+
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+
+        // A notified thread always tries again before giving up,
+        // so it can't swallow a wakeup meant for another one.
+        let taken = loop {
+            if let Some(taken) = try_take() {
+                break Some(taken);
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break None;
+                    }
+                    guard = self.condvar.wait_timeout(guard, deadline - now).unwrap().0;
+                }
+                None => guard = self.condvar.wait(guard).unwrap(),
+            }
+        };
+
+        self.waiting.fetch_sub(1, Ordering::Relaxed);
+
+        taken
+    }
+
+    /// Async version of [wait_until](Waiters::wait_until).
+    /// Queues the task's waker if `try_take` returns `None`.
+    ///
+    /// `registration` belongs to the polling future, so that polling it
+    /// again replaces its waker instead of queuing another one.
+    /// The future must pass it to [deregister](Waiters::deregister) when dropped.
+    pub fn poll_take<R, F>(
+        &self,
+        cx: &mut Context<'_>,
+        registration: &mut Registration,
+        mut try_take: F,
+    ) -> Poll<R>
+    where
+        F: FnMut() -> Option<R>,
+    {
+        if let Some(taken) = try_take() {
+            self.finish(registration, true);
+            return Poll::Ready(taken);
+        }
+
+        self.register(registration, cx.waker());
+
+        // Pairs with the loads in `notify()`, same as in `wait_until()`
+        fence(Ordering::SeqCst);
+
+        match try_take() {
+            Some(taken) => {
+                self.finish(registration, true);
+                Poll::Ready(taken)
+            }
+            None => Poll::Pending,
+        }
+    }
+
+    /// Stores `waker` in the slot of `registration` and queues it,
+    /// unless it is queued already.
+    fn register(&self, registration: &mut Registration, waker: &Waker) {
+        let slot = registration.slot.get_or_insert_with(|| {
+            Arc::new(Slot {
+                state: AtomicU8::new(IDLE),
+                waker: AtomicWaker::new(),
+                next: AtomicPtr::new(null_mut()),
+            })
+        });
+
+        slot.waker.register(waker);
+
+        // Only the owner moves a slot into QUEUED, so it can't become QUEUED meanwhile
+        if slot.state.load(Ordering::Acquire) == QUEUED {
+            return;
+        }
+
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        slot.state.store(QUEUED, Ordering::Release);
+
+        let node = Arc::into_raw(Arc::clone(slot)) as *mut Slot;
+        let mut old_head = self.incoming.load(Ordering::Relaxed);
+
+        loop {
+            slot.next.store(old_head, Ordering::Relaxed);
+
+            match self.incoming.compare_exchange_weak(
+                old_head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(old) => old_head = old,
+            };
+        }
+    }
+
+    /// Gives up the slot of a future that is dropped.
+    pub fn deregister(&self, registration: &mut Registration) {
+        self.finish(registration, false);
+    }
+
+    /// Gives up the slot of `registration`. If the future was woken
+    /// but didn't take a pointer, another one is woken in its place.
+    fn finish(&self, registration: &mut Registration, took: bool) {
+        let slot = match registration.slot.take() {
+            Some(slot) => slot,
+            None => return,
+        };
+
+        drop(slot.waker.take());
+
+        // Counted before the slot can be seen dead, so that it is never negative
+        let dead = self.dead.fetch_add(1, Ordering::Relaxed) + 1;
+
+        match slot.state.swap(DEAD, Ordering::AcqRel) {
+            QUEUED => {
+                let queued = self.queued.fetch_sub(1, Ordering::SeqCst) - 1;
+
+                // Drop dead slots once there are more of them than live ones
+                if dead > queued {
+                    self.drain();
+                }
+            }
+            state => {
+                self.dead.fetch_sub(1, Ordering::Relaxed);
+
+                if state == WOKEN && !took {
+                    fence(Ordering::SeqCst);
+
+                    if self.queued.load(Ordering::SeqCst) != 0 {
+                        self.owed.fetch_add(1, Ordering::SeqCst);
+                        self.drain();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hands out the `owed` wakeups, unless another thread is doing it.
+    fn drain(&self) {
+        while !self.draining.swap(true, Ordering::SeqCst) {
+            let queue = unsafe { &mut *self.queue.get() };
+
+            self.refill(queue);
+
+            if self.dead.load(Ordering::Relaxed) > queue.len() / 2 {
+                let len = queue.len();
+                queue.retain(|slot| slot.state.load(Ordering::Acquire) != DEAD);
+                self.dead.fetch_sub(len - queue.len(), Ordering::Relaxed);
+            }
+
+            loop {
+                let owed = self.owed.load(Ordering::SeqCst);
+
+                if owed == 0 {
+                    break;
+                }
+
+                match self.pop_queued(queue) {
+                    Some(slot) => {
+                        self.owed.fetch_sub(1, Ordering::SeqCst);
+                        slot.waker.wake();
+                    }
+                    /*
+                     * Every slot queued before the wakeups counted in `owed`
+                     * is out of `incoming` by now, so they are not needed.
+                     * Unless more were added since.
+                     */
+                    None => {
+                        if self
+                            .owed
+                            .compare_exchange(owed, 0, Ordering::SeqCst, Ordering::SeqCst)
+                            .is_ok()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            self.draining.store(false, Ordering::SeqCst);
+
+            // Wakeups added by threads that found `draining` set
+            if self.owed.load(Ordering::SeqCst) == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Takes the oldest slot still waiting out of `queue`,
+    /// dropping the dead ones in front of it.
+    fn pop_queued(&self, queue: &mut VecDeque<Arc<Slot>>) -> Option<Arc<Slot>> {
+        loop {
+            let slot = match queue.pop_front() {
+                Some(slot) => slot,
+                None => {
+                    self.refill(queue);
+                    queue.pop_front()?
+                }
+            };
+
+            match slot
+                .state
+                .compare_exchange(QUEUED, WOKEN, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    self.queued.fetch_sub(1, Ordering::SeqCst);
+                    return Some(slot);
+                }
+                Err(_) => {
+                    self.dead.fetch_sub(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Moves the slots in `incoming` to the back of `queue`, oldest first.
+    fn refill(&self, queue: &mut VecDeque<Arc<Slot>>) {
+        let mut node = self.incoming.swap(null_mut(), Ordering::SeqCst);
+        let mut reversed = null_mut::<Slot>();
+
+        while !node.is_null() {
+            let next = unsafe { (*node).next.load(Ordering::Relaxed) };
+            unsafe { (*node).next.store(reversed, Ordering::Relaxed) };
+            reversed = node;
+            node = next;
+        }
+
+        while !reversed.is_null() {
+            let slot = unsafe { Arc::from_raw(reversed) };
+            reversed = slot.next.load(Ordering::Relaxed);
+            queue.push_back(slot);
+        }
+    }
+}
+
+impl Drop for Waiters {
+    fn drop(&mut self) {
+        let mut node = *self.incoming.get_mut();
+
+        while !node.is_null() {
+            let slot = unsafe { Arc::from_raw(node) };
+            node = slot.next.load(Ordering::Relaxed);
+        }
+    }
+}
+
+/// Holds the waker of a future while another thread may be waking it,
+/// in the same way as `AtomicWaker` of the `futures` crate.
+struct AtomicWaker {
+    state: AtomicU8,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+/// Nobody is touching `waker`.
+const UNLOCKED: u8 = 0;
+/// The owner is storing a new waker.
+const REGISTERING: u8 = 0b01;
+/// Another thread is taking the waker.
+const WAKING: u8 = 0b10;
+
+/// `waker` is only accessed by whoever moved `state` out of UNLOCKED.
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    fn new() -> Self {
+        AtomicWaker {
+            state: AtomicU8::new(UNLOCKED),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Stores `waker`, unless it would wake the same task as the stored one.
+    /// Only one thread may call this at a time.
+    fn register(&self, waker: &Waker) {
+        match self.state.compare_exchange(
+            UNLOCKED,
+            REGISTERING,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                let stored = unsafe { &mut *self.waker.get() };
+
+                match stored {
+                    Some(old) if old.will_wake(waker) => {}
+                    _ => *stored = Some(waker.clone()),
+                }
+
+                if self
+                    .state
+                    .compare_exchange(REGISTERING, UNLOCKED, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+                {
+                    // Somebody tried to wake it meanwhile, do it for them
+                    let waker = stored.take();
+                    self.state.store(UNLOCKED, Ordering::Release);
+
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+            // Being woken right now, the new waker might be missed
+            Err(_) => waker.wake_by_ref(),
+        }
+    }
+
+    /// Takes the waker out, if it isn't being registered. In that case
+    /// `register()` wakes the new waker itself.
+    fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            UNLOCKED => {
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKING, Ordering::Release);
+                waker
+            }
+            _ => None,
+        }
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// A thread that runs a task every `interval` until it is dropped.
+pub(crate) struct Periodic {
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Periodic {
+    pub fn spawn<F>(name: &str, interval: Duration, mut task: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let should_stop = Arc::clone(&stop);
+
+        let worker = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                while !should_stop.load(Ordering::Acquire) {
+                    task();
+                    thread::park_timeout(interval);
+                }
+            })
+            .unwrap();
+
+        Periodic {
+            stop,
+            worker: Some(worker),
+        }
+    }
+}
+
+/// Stops the thread and waits for it to exit.
+impl Drop for Periodic {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+
+        if let Some(worker) = self.worker.take() {
+            worker.thread().unpark();
+            let _ = worker.join();
+        }
+    }
+}
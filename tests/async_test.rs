@@ -0,0 +1,225 @@
+use lock_free_freelist::{FreeList, Pool};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::Duration,
+};
+
+struct Unparker(Thread);
+
+impl Wake for Unparker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Arc::new(Unparker(thread::current())).into();
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CountWakes(AtomicUsize);
+
+impl Wake for CountWakes {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn poll_once<F: Future + Unpin>(future: &mut F, waker: &Waker) -> Poll<F::Output> {
+    Pin::new(future).poll(&mut Context::from_waker(waker))
+}
+
+#[test]
+fn reuse_async_waits_for_return() {
+    let free_list = Arc::new(FreeList::<Box<u32>>::new());
+    let allocated = free_list.alloc(1);
+
+    let returner = {
+        let free_list = Arc::clone(&free_list);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            drop(free_list.alloc(2));
+        })
+    };
+
+    let reused = block_on(free_list.reuse_async(3));
+    assert_eq!(**reused, 3);
+
+    returner.join().unwrap();
+    drop(allocated);
+}
+
+#[test]
+fn dropped_future_does_not_lose_wakeup() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    let first_wakes = Arc::new(CountWakes::default());
+    let second_wakes = Arc::new(CountWakes::default());
+    let first_waker = Waker::from(Arc::clone(&first_wakes));
+    let second_waker = Waker::from(Arc::clone(&second_wakes));
+
+    let mut first = free_list.reuse_async(1);
+    let mut second = free_list.reuse_async(2);
+
+    assert!(poll_once(&mut first, &first_waker).is_pending());
+    assert!(poll_once(&mut second, &second_waker).is_pending());
+
+    drop(free_list.alloc(0));
+
+    // one pointer wakes one future
+    assert_eq!(first_wakes.0.load(Ordering::SeqCst), 1);
+    assert_eq!(second_wakes.0.load(Ordering::SeqCst), 0);
+
+    // first is cancelled after being woken, second is woken in its place
+    drop(first);
+    assert_eq!(second_wakes.0.load(Ordering::SeqCst), 1);
+
+    match poll_once(&mut second, &second_waker) {
+        Poll::Ready(reused) => assert_eq!(**reused, 2),
+        Poll::Pending => panic!("pointer was lost"),
+    };
+}
+
+#[test]
+fn bounded_pool_get_async() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0).with_limit(1));
+    let held = pool.get();
+
+    let waiter = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            let mut object = block_on(pool.get_async());
+            **object = 5;
+        })
+    };
+
+    thread::sleep(Duration::from_millis(20));
+    drop(held);
+    waiter.join().unwrap();
+
+    assert_eq!(pool.live(), 1);
+    assert_eq!(**pool.get(), 5);
+}
+
+#[test]
+fn repolling_keeps_one_waker() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    let old_wakes = Arc::new(CountWakes::default());
+    let new_wakes = Arc::new(CountWakes::default());
+    let old_waker = Waker::from(Arc::clone(&old_wakes));
+    let new_waker = Waker::from(Arc::clone(&new_wakes));
+
+    let mut future = free_list.reuse_async(1);
+
+    for _ in 0..10 {
+        assert!(poll_once(&mut future, &old_waker).is_pending());
+    }
+    assert!(poll_once(&mut future, &new_waker).is_pending());
+
+    drop(free_list.alloc(0));
+
+    assert_eq!(old_wakes.0.load(Ordering::SeqCst), 0);
+    assert_eq!(new_wakes.0.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn cancelled_future_is_not_woken() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    let wakes = Arc::new(CountWakes::default());
+    let waker = Waker::from(Arc::clone(&wakes));
+
+    let mut future = free_list.reuse_async(1);
+    assert!(poll_once(&mut future, &waker).is_pending());
+    drop(future);
+
+    drop(free_list.alloc(0));
+
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn one_future_woken_per_pointer() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    let wakes = (0..4)
+        .map(|_| Arc::new(CountWakes::default()))
+        .collect::<Vec<_>>();
+    let wakers = wakes
+        .iter()
+        .map(|wakes| Waker::from(Arc::clone(wakes)))
+        .collect::<Vec<_>>();
+
+    let mut futures = (0..4).map(|i| free_list.reuse_async(i)).collect::<Vec<_>>();
+    for (future, waker) in futures.iter_mut().zip(&wakers) {
+        assert!(poll_once(future, waker).is_pending());
+    }
+
+    let woken = || {
+        wakes
+            .iter()
+            .map(|wakes| wakes.0.load(Ordering::SeqCst))
+            .collect::<Vec<_>>()
+    };
+
+    drop(free_list.alloc(0));
+    assert_eq!(woken(), [1, 0, 0, 0]);
+
+    drop(free_list.alloc(0));
+    assert_eq!(woken(), [1, 1, 0, 0]);
+
+    // the woken ones take the pointers, the others are left alone
+    let reused = futures
+        .iter_mut()
+        .zip(&wakers)
+        .take(2)
+        .map(|(future, waker)| match poll_once(future, waker) {
+            Poll::Ready(reused) => reused,
+            Poll::Pending => panic!("woken future didn't get the pointer"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(woken(), [1, 1, 0, 0]);
+
+    // returning one wakes the next in line
+    drop(reused);
+    assert_eq!(woken(), [1, 1, 1, 1]);
+}
+
+#[test]
+fn bounded_pool_get_async_many_tasks() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0).with_limit(2));
+
+    let threads = (0..8)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                for _ in 0..500 {
+                    let mut object = block_on(pool.get_async());
+                    **object += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    assert!(pool.live() <= 2);
+}
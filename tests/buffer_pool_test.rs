@@ -0,0 +1,116 @@
+use lock_free_freelist::BufferPool;
+use std::{sync::Arc, thread};
+
+#[test]
+fn rounds_up_to_size_class() {
+    let pool = BufferPool::new();
+
+    assert_eq!(pool.get(0).capacity(), 64);
+    assert_eq!(pool.get(64).capacity(), 64);
+    assert_eq!(pool.get(65).capacity(), 128);
+    assert_eq!(pool.get(1000).capacity(), 1024);
+    assert_eq!(
+        pool.get(BufferPool::MAX_CAPACITY).capacity(),
+        BufferPool::MAX_CAPACITY
+    );
+}
+
+#[test]
+fn returns_to_its_class() {
+    let pool = BufferPool::new();
+
+    let mut small = pool.get(100);
+    let mut large = pool.get(5000);
+    small.extend_from_slice(b"small");
+    large.extend_from_slice(b"large");
+    let small_ptr = small.as_ptr();
+    let large_ptr = large.as_ptr();
+    drop(small);
+    drop(large);
+
+    assert_eq!(pool.retained(), 128 + 8192);
+
+    let large = pool.get(8000);
+    let small = pool.get(128);
+    assert_eq!(large.as_ptr(), large_ptr);
+    assert_eq!(small.as_ptr(), small_ptr);
+    assert!(large.is_empty() && small.is_empty());
+    assert_eq!(pool.retained(), 0);
+}
+
+#[test]
+fn grown_buffer_moves_to_bigger_class() {
+    let pool = BufferPool::new();
+
+    let mut buf = pool.get(64);
+    buf.resize(65, 0);
+    assert_eq!(buf.capacity(), 128);
+    drop(buf);
+
+    assert_eq!(pool.retained(), 128);
+}
+
+#[test]
+fn unpoolable_buffers_are_dropped() {
+    let pool = BufferPool::new();
+
+    drop(pool.get(BufferPool::MAX_CAPACITY + 1));
+    assert_eq!(pool.retained(), 0);
+
+    let mut buf = pool.get(64);
+    buf.shrink_to(10);
+    buf.reserve_exact(90);
+    assert!(!buf.capacity().is_power_of_two());
+    drop(buf);
+    assert_eq!(pool.retained(), 0);
+
+    let detached = pool.get(64).into_vec();
+    assert_eq!(detached.capacity(), 64);
+    assert_eq!(pool.retained(), 0);
+}
+
+#[test]
+fn retained_bytes_are_capped() {
+    let pool = BufferPool::new().with_retained_bytes(4096);
+
+    let bufs = (0..10).map(|_| pool.get(1024)).collect::<Vec<_>>();
+    drop(bufs);
+    assert_eq!(pool.retained(), 4096);
+
+    let bufs = (0..10).map(|_| pool.get(64)).collect::<Vec<_>>();
+    drop(bufs);
+    assert_eq!(pool.retained(), 4096 + 640);
+}
+
+#[test]
+fn shared_between_threads() {
+    let pool = BufferPool::new();
+
+    thread::scope(|scope| {
+        for t in 0..8 {
+            let pool = &pool;
+            scope.spawn(move || {
+                for i in 0..1000 {
+                    let mut buf = pool.get(64 << ((t + i) % 6));
+                    buf.push(i as u8);
+                    assert_eq!(buf.len(), 1);
+                }
+            });
+        }
+    });
+
+    assert!(pool.retained() > 0);
+}
+
+#[test]
+fn taken_out_buffers_are_not_returned() {
+    let pool = Arc::new(BufferPool::new());
+
+    let borrowed = pool.get(100).into_vec();
+    let shared = pool.get_shared(100).into_vec();
+    assert_eq!(borrowed.capacity(), 128);
+    assert_eq!(shared.capacity(), 128);
+
+    assert_eq!(pool.retained(), 0);
+    assert_eq!(Arc::strong_count(&pool), 1);
+}
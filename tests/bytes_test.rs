@@ -0,0 +1,84 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{BufMut, Bytes};
+use lock_free_freelist::BufferPool;
+use std::{sync::Arc, thread};
+
+fn leaked_pool() -> &'static BufferPool {
+    Box::leak(Box::new(BufferPool::new()))
+}
+
+fn write_frame<B: BufMut>(buf: &mut B) {
+    buf.put_u16(7);
+    buf.put_slice(b"frame");
+}
+
+#[test]
+fn frozen_buffer_returns_after_last_clone() {
+    let pool = leaked_pool();
+
+    let mut buf = pool.get(1000);
+    buf.put_slice(b"hello world");
+    let ptr = buf.as_ptr();
+
+    let bytes = buf.freeze();
+    assert_eq!(&bytes[..], b"hello world");
+    assert_eq!(bytes.as_ptr(), ptr);
+
+    let hello = bytes.slice(..5);
+    let clones = (0..4).map(|_| bytes.clone()).collect::<Vec<Bytes>>();
+    drop(bytes);
+
+    thread::spawn(move || drop(clones)).join().unwrap();
+    assert_eq!(pool.retained(), 0);
+
+    assert_eq!(&hello[..], b"hello");
+    drop(hello);
+    assert_eq!(pool.retained(), 1024);
+
+    assert_eq!(pool.get(1000).as_ptr(), ptr);
+}
+
+#[test]
+fn frozen_shared_buffer_returns_after_last_clone() {
+    let pool = Arc::new(BufferPool::new());
+
+    let mut buf = pool.get_shared(100);
+    write_frame(&mut buf);
+    let ptr = buf.as_ptr();
+
+    let bytes = buf.freeze();
+    assert_eq!(&bytes[..], b"\0\x07frame");
+
+    let clone = bytes.clone();
+    thread::spawn(move || drop(bytes)).join().unwrap();
+    assert_eq!(pool.retained(), 0);
+
+    drop(clone);
+    assert_eq!(pool.retained(), 128);
+    assert_eq!(pool.get(100).as_ptr(), ptr);
+}
+
+#[test]
+fn frozen_buffer_keeps_pool_alive() {
+    let pool = Arc::new(BufferPool::new());
+
+    let bytes = pool.get_shared(64).freeze();
+    drop(pool);
+
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn buffer_grown_by_buf_mut_is_kept() {
+    let pool = BufferPool::new();
+
+    let mut buf = pool.get(64);
+    for _ in 0..100 {
+        write_frame(&mut buf);
+    }
+    assert!(buf.capacity().is_power_of_two());
+    drop(buf);
+
+    assert_eq!(pool.retained(), 1024);
+}
@@ -0,0 +1,75 @@
+use lock_free_freelist::{StringPool, VecPool};
+use std::{rc::Rc, thread};
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn string_keeps_its_buffer() {
+    let pool = StringPool::new();
+
+    let mut s = pool.get_with_capacity(100);
+    s.push_str("hello");
+    let buffer = s.as_ptr();
+    drop(s);
+
+    assert_eq!(pool.len(), 1);
+
+    let s = pool.get();
+    assert!(s.is_empty());
+    assert!(s.capacity() >= 100);
+    assert_eq!(s.as_ptr(), buffer);
+}
+
+#[test]
+fn vec_elements_are_dropped_on_return() {
+    let pool = VecPool::<Rc<u32>>::new();
+    let shared = Rc::new(5);
+
+    let mut v = pool.get();
+    v.extend((0..10).map(|_| Rc::clone(&shared)));
+    assert_eq!(Rc::strong_count(&shared), 11);
+    drop(v);
+
+    assert_eq!(Rc::strong_count(&shared), 1);
+    assert!(pool.get().capacity() >= 10);
+}
+
+#[test]
+fn max_capacity_and_full_pool() {
+    let pool = VecPool::<u8>::new().with_max_capacity(64);
+
+    let mut big = pool.get_with_capacity(65);
+    big.push(1);
+    drop(big);
+    assert!(pool.is_empty());
+
+    let vecs = (0..CAPACITY + 5)
+        .map(|_| pool.get_with_capacity(8))
+        .collect::<Vec<_>>();
+    drop(vecs);
+    assert_eq!(pool.len(), CAPACITY);
+
+    let detached = pool.get().into_inner();
+    assert!(detached.capacity() >= 8);
+    assert_eq!(pool.len(), CAPACITY - 1);
+}
+
+#[test]
+fn shared_between_threads() {
+    let pool = StringPool::new();
+
+    thread::scope(|scope| {
+        for t in 0..8 {
+            let pool = &pool;
+            scope.spawn(move || {
+                for i in 0..1000 {
+                    let mut s = pool.get();
+                    assert!(s.is_empty());
+                    s.push_str(&format!("{}-{}", t, i));
+                }
+            });
+        }
+    });
+
+    assert!(!pool.is_empty());
+}
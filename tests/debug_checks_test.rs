@@ -0,0 +1,97 @@
+#![cfg(feature = "debug-checks")]
+
+use lock_free_freelist::{FreeList, OverflowPolicy, Pool, Reuse, SmartPointer};
+use std::{
+    ops::{Deref, DerefMut},
+    ptr::null_mut,
+    sync::Arc,
+};
+
+/// A smart pointer that leaks its contents and, to simulate
+/// a buggy implementation, can give back another pointer from `into_raw`.
+struct Raw {
+    ptr: *mut u64,
+    redirect: *mut u64,
+}
+
+impl Deref for Raw {
+    type Target = u64;
+
+    fn deref(&self) -> &u64 {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl DerefMut for Raw {
+    fn deref_mut(&mut self) -> &mut u64 {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+unsafe impl SmartPointer for Raw {
+    unsafe fn from_raw(ptr: *mut u64) -> Self {
+        Raw {
+            ptr,
+            redirect: null_mut(),
+        }
+    }
+
+    fn into_raw(smart_pointer: Self) -> *mut u64 {
+        if smart_pointer.redirect.is_null() {
+            smart_pointer.ptr
+        } else {
+            smart_pointer.redirect
+        }
+    }
+
+    fn new(contents: u64) -> Self {
+        unsafe { Raw::from_raw(Box::into_raw(Box::new(contents))) }
+    }
+}
+
+#[test]
+#[should_panic(expected = "while already in it")]
+fn double_throw_panics() {
+    let free_list = FreeList::<Raw>::new();
+
+    let first = free_list.alloc(1);
+    let ptr = first.ptr;
+    drop(first);
+
+    // Adopting a pointer that is already in the free list
+    drop(Reuse::new(unsafe { Raw::from_raw(ptr) }, &free_list));
+}
+
+#[test]
+#[should_panic(expected = "didn't allocate it")]
+fn foreign_pointer_panics() {
+    let free_list = FreeList::<Raw>::new();
+
+    let mut reused = free_list.alloc(1);
+    reused.redirect = Box::into_raw(Box::new(2));
+    drop(reused);
+}
+
+#[test]
+fn correct_use_passes() {
+    let parent = Arc::new(FreeList::<Box<u64>>::new());
+    let free_list = FreeList::<Box<u64>>::new()
+        .with_overflow_policy(OverflowPolicy::Forward(Arc::clone(&parent)));
+
+    for _ in 0..3 {
+        let boxes = (0..100)
+            .map(|i| free_list.reuse_or_alloc(i))
+            .collect::<Vec<_>>();
+        drop(boxes);
+    }
+
+    // Adopted pointers can be thrown too
+    drop(Reuse::new(Box::new(5), &free_list));
+
+    assert_eq!(free_list.drain().count(), free_list.capacity());
+    assert_eq!(free_list.trim_to(0), 0);
+
+    let pool = Pool::<Box<u64>>::new(|| 0);
+    drop(pool.get());
+    drop(pool.get());
+}
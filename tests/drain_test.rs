@@ -0,0 +1,75 @@
+use lock_free_freelist::{FreeList, OverflowPolicy, Pool, Reuse};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn drain_takes_spilled_pointers_too() {
+    let free_list = FreeList::<Box<u32>>::new().with_overflow_policy(OverflowPolicy::Spill);
+
+    let allocated = (0..CAPACITY + 10)
+        .map(|i| free_list.alloc(i as u32))
+        .collect::<Vec<_>>();
+    drop(allocated);
+
+    assert_eq!(free_list.drain().count(), CAPACITY + 10);
+    assert!(free_list.reuse(0).is_err());
+}
+
+#[test]
+fn drained_pointers_can_be_wrapped_again() {
+    let pool = Pool::<Box<u32>>::new(|| 0);
+    drop(pool.get());
+    assert_eq!(pool.live(), 1);
+
+    let drained = pool.free_list().drain().collect::<Vec<_>>();
+    assert_eq!(pool.live(), 0);
+
+    let wrapped = drained
+        .into_iter()
+        .map(|boxed| Reuse::new(boxed, pool.free_list()))
+        .collect::<Vec<_>>();
+    assert_eq!(pool.live(), 1);
+
+    drop(wrapped);
+    assert_eq!(pool.free_list().len(), 1);
+}
+
+#[test]
+fn shrink_while_in_use() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let threads = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let objects = (0..8).map(|_| pool.get()).collect::<Vec<_>>();
+                    drop(objects);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for _ in 0..1000 {
+        pool.free_list().trim_to(4);
+        pool.free_list().shrink();
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(pool.live(), pool.free_list().len());
+    pool.free_list().shrink();
+    assert_eq!(pool.live(), 0);
+}
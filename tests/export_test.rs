@@ -0,0 +1,107 @@
+#![cfg(any(feature = "prometheus", feature = "json"))]
+
+use lock_free_freelist::{registry::PoolSnapshot, Stats};
+
+fn pools() -> Vec<PoolSnapshot> {
+    vec![
+        PoolSnapshot {
+            type_name: "u64",
+            label: Some("a \"quoted\"\\label\n".to_string()),
+            len: 2,
+            capacity: 64,
+            stats: Stats {
+                reuses: 5,
+                allocs: 3,
+                throws: 7,
+                overflows: 1,
+                rejected: 6,
+                cas_retries: 4,
+                len: 2,
+                peak_len: 3,
+            },
+        },
+        PoolSnapshot {
+            type_name: "alloc::string::String",
+            label: None,
+            len: 0,
+            capacity: 64,
+            stats: Stats::default(),
+        },
+    ]
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn prometheus_text() {
+    let text = lock_free_freelist::export::prometheus(&pools());
+
+    assert!(text.contains("# TYPE freelist_reuse_total counter\n"));
+    assert!(text.contains("# TYPE freelist_len gauge\n"));
+    assert!(text
+        .contains("freelist_reuse_total{type=\"u64\",label=\"a \\\"quoted\\\"\\\\label\\n\"} 5\n"));
+    assert!(text.contains("freelist_alloc_total{type=\"alloc::string::String\",label=\"\"} 0\n"));
+    assert!(text.contains("freelist_cas_retry_total{type=\"u64\","));
+    assert!(text.ends_with("freelist_capacity{type=\"alloc::string::String\",label=\"\"} 64\n"));
+
+    for name in &[
+        "freelist_reuse_total",
+        "freelist_alloc_total",
+        "freelist_throw_total",
+        "freelist_overflow_total",
+        "freelist_reject_total",
+        "freelist_cas_retry_total",
+        "freelist_len",
+        "freelist_peak_len",
+        "freelist_capacity",
+    ] {
+        assert_eq!(
+            text.matches(&format!("\n{}{{", name)).count(),
+            2,
+            "{}",
+            name
+        );
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_array() {
+    let json = lock_free_freelist::export::json(&pools());
+
+    assert_eq!(
+        json,
+        "[{\"type\":\"u64\",\"label\":\"a \\\"quoted\\\"\\\\label\\n\",\"len\":2,\"capacity\":64,\
+         \"peak_len\":3,\"reuses\":5,\"allocs\":3,\"throws\":7,\"overflows\":1,\"rejected\":6,\"cas_retries\":4},\
+         {\"type\":\"alloc::string::String\",\"label\":null,\"len\":0,\"capacity\":64,\
+         \"peak_len\":0,\"reuses\":0,\"allocs\":0,\"throws\":0,\"overflows\":0,\"rejected\":0,\"cas_retries\":0}]"
+    );
+    assert_eq!(lock_free_freelist::export::json(&[]), "[]");
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn prometheus_series_are_unique() {
+    use lock_free_freelist::{export, FreeList};
+
+    let first = FreeList::<Box<i16>>::new();
+    let second = FreeList::<Box<i16>>::new();
+    drop(first.alloc(1));
+    drop(second.alloc(2));
+    drop(second.alloc(3));
+
+    let pools = lock_free_freelist::registry::snapshot()
+        .into_iter()
+        .filter(|pool| pool.type_name == "i16")
+        .collect::<Vec<_>>();
+    assert_eq!(pools.len(), 2);
+
+    let text = export::prometheus(&pools);
+
+    assert_eq!(text.matches("\nfreelist_len{").count(), 1);
+    assert!(text.contains("freelist_len{type=\"i16\",label=\"\"} 3\n"));
+    assert!(text.contains("freelist_alloc_total{type=\"i16\",label=\"\"} 3\n"));
+    assert!(text.contains(&format!(
+        "freelist_capacity{{type=\"i16\",label=\"\"}} {}\n",
+        2 * first.capacity()
+    )));
+}
@@ -0,0 +1,109 @@
+//! Runs with `FreeListAlloc` as the global allocator of this test binary.
+
+use lock_free_freelist::{FreeList, FreeListAlloc};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    thread,
+};
+
+#[global_allocator]
+static GLOBAL: FreeListAlloc<System> = FreeListAlloc::new(System);
+
+#[test]
+fn small_blocks_are_reused() {
+    let alloc = FreeListAlloc::new(System);
+    let layout = Layout::from_size_align(100, 8).unwrap();
+
+    unsafe {
+        let block = alloc.alloc(layout);
+        block.write_bytes(0xab, 100);
+        alloc.dealloc(block, layout);
+        assert_eq!(alloc.cached(), 1);
+
+        // same class of 128 bytes
+        let other_layout = Layout::from_size_align(120, 16).unwrap();
+        let reused = alloc.alloc(other_layout);
+        assert_eq!(reused, block);
+        assert_eq!(alloc.cached(), 0);
+
+        // grows in place within the class
+        assert_eq!(alloc.realloc(reused, other_layout, 128), reused);
+
+        let zeroed = alloc.alloc_zeroed(Layout::from_size_align(128, 1).unwrap());
+        assert!(std::slice::from_raw_parts(zeroed, 128)
+            .iter()
+            .all(|b| *b == 0));
+
+        alloc.dealloc(reused, Layout::from_size_align(128, 16).unwrap());
+        alloc.dealloc(zeroed, Layout::from_size_align(128, 1).unwrap());
+    }
+}
+
+#[test]
+fn big_and_overaligned_blocks_pass_through() {
+    let alloc = FreeListAlloc::new(System);
+
+    unsafe {
+        for layout in &[
+            Layout::from_size_align(4096, 8).unwrap(),
+            Layout::from_size_align(64, 64).unwrap(),
+        ] {
+            let block = alloc.alloc(*layout);
+            assert_eq!(block.align_offset(layout.align()), 0);
+            alloc.dealloc(block, *layout);
+        }
+
+        assert_eq!(alloc.cached(), 0);
+
+        // moves out of the cached classes and back
+        let small = Layout::from_size_align(16, 8).unwrap();
+        let block = alloc.alloc(small);
+        block.write_bytes(7, 16);
+        let grown = alloc.realloc(block, small, 2000);
+        assert_eq!(*grown.add(15), 7);
+        let big = Layout::from_size_align(2000, 8).unwrap();
+        let shrunk = alloc.realloc(grown, big, 8);
+        assert_eq!(*shrunk, 7);
+        // the first block, cached when it grew, was reused when it shrunk
+        assert_eq!(shrunk, block);
+        alloc.dealloc(shrunk, Layout::from_size_align(8, 8).unwrap());
+        assert_eq!(alloc.cached(), 1);
+    }
+}
+
+#[test]
+fn program_runs_under_it() {
+    let threads = (0..8)
+        .map(|t| {
+            thread::spawn(move || {
+                let mut map = HashMap::new();
+
+                for i in 0..2000 {
+                    map.insert(i, format!("{}-{}", t, i));
+                    if i % 3 == 0 {
+                        map.remove(&(i / 2));
+                    }
+                }
+
+                let free_list = FreeList::<Box<String>>::new();
+                for value in map.values() {
+                    drop(free_list.reuse_or_alloc(value.clone()));
+                }
+
+                map.len()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        assert!(handle.join().unwrap() > 0);
+    }
+
+    let text = (0..10_000)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    assert_eq!(text.split(',').count(), 10_000);
+    assert!(GLOBAL.cached() > 0);
+}
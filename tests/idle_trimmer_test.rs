@@ -0,0 +1,97 @@
+use lock_free_freelist::{FreeList, IdleTrimmer};
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn only_idle_pointers_are_trimmed() {
+    let free_list = FreeList::<Box<u32>>::new();
+    free_list.prefill(10, || 0);
+
+    assert_eq!(free_list.trim_idle(Duration::from_millis(20)), 0);
+    thread::sleep(Duration::from_millis(40));
+
+    // reusing and throwing back makes these fresh again
+    let reused = (0..4)
+        .map(|i| free_list.reuse(i).unwrap())
+        .collect::<Vec<_>>();
+    drop(reused);
+
+    assert_eq!(free_list.trim_idle(Duration::from_millis(20)), 6);
+    assert_eq!(free_list.len(), 4);
+
+    thread::sleep(Duration::from_millis(40));
+    assert_eq!(free_list.trim_idle(Duration::from_millis(20)), 4);
+    assert!(free_list.is_empty());
+}
+
+#[test]
+fn fresh_pointers_are_never_trimmed() {
+    let free_list = FreeList::<Box<u32>>::new();
+    assert_eq!(free_list.trim_idle(Duration::from_millis(10)), 0);
+
+    thread::sleep(Duration::from_millis(30));
+    drop(free_list.alloc(1));
+
+    assert_eq!(free_list.trim_idle(Duration::from_millis(10)), 0);
+    assert_eq!(free_list.len(), 1);
+}
+
+#[test]
+fn huge_max_age_keeps_everything() {
+    let free_list = FreeList::<Box<u32>>::new();
+    free_list.prefill(4, || 0);
+
+    assert_eq!(free_list.trim_idle(Duration::MAX), 0);
+    thread::sleep(Duration::from_millis(5));
+    assert_eq!(free_list.trim_idle(Duration::MAX), 0);
+    assert_eq!(free_list.len(), 4);
+}
+
+#[test]
+fn trimmer_empties_unused_free_list() {
+    let free_list = Arc::new(FreeList::<Box<u32>>::new());
+    free_list.prefill(16, || 0);
+
+    let _trimmer = IdleTrimmer::spawn(
+        Arc::clone(&free_list),
+        Duration::from_millis(5),
+        Duration::from_millis(1),
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !free_list.is_empty() {
+        assert!(Instant::now() < deadline, "idle pointers were not trimmed");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn trim_idle_while_in_use() {
+    let free_list = Arc::new(FreeList::<Box<u32>>::new());
+    let _trimmer = IdleTrimmer::spawn(
+        Arc::clone(&free_list),
+        Duration::from_millis(0),
+        Duration::from_micros(100),
+    );
+
+    let threads = (0..4)
+        .map(|_| {
+            let free_list = Arc::clone(&free_list);
+            thread::spawn(move || {
+                for i in 0..2000 {
+                    let objects = (0..4)
+                        .map(|_| free_list.reuse_or_alloc(i))
+                        .collect::<Vec<_>>();
+                    assert!(objects.iter().all(|object| ***object == i));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+}
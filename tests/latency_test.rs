@@ -0,0 +1,65 @@
+#![cfg(feature = "latency")]
+
+use lock_free_freelist::{FreeList, OverflowPolicy};
+use std::{thread, time::Duration};
+
+#[test]
+fn counts_every_operation() {
+    let free_list = FreeList::<Box<u64>>::new();
+
+    assert_eq!(free_list.latencies().reuse.count, 0);
+    assert_eq!(free_list.latencies().reuse.max, Duration::ZERO);
+
+    let boxes = (0..10).map(|i| free_list.alloc(i)).collect::<Vec<_>>();
+    drop(boxes);
+
+    for i in 0..20 {
+        drop(free_list.reuse(i));
+    }
+
+    let latencies = free_list.latencies();
+
+    assert_eq!(latencies.alloc.count, 10);
+    assert_eq!(latencies.reuse.count, 20);
+    assert_eq!(latencies.drop.count, 30);
+}
+
+#[test]
+fn percentiles_are_ordered() {
+    let free_list = FreeList::<Box<u64>>::new();
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for i in 0..1000 {
+                    drop(free_list.reuse_or_alloc(i));
+                }
+            });
+        }
+    });
+
+    let reuse = free_list.latencies().reuse;
+
+    assert_eq!(reuse.count, 4000);
+    assert!(reuse.p50 <= reuse.p99);
+    assert!(reuse.p99 <= reuse.p999);
+    assert!(reuse.p999 <= reuse.max);
+}
+
+#[test]
+fn max_is_exact() {
+    // Dropping a `Reuse` hands the pointer to the callback, which sleeps.
+    let slow = FreeList::<Box<u64>>::new().with_overflow_policy(OverflowPolicy::Callback(
+        Box::new(|_| thread::sleep(Duration::from_millis(20))),
+    ));
+
+    let boxes = (0..std::mem::size_of::<usize>() * 8 + 1)
+        .map(|i| slow.alloc(i as u64))
+        .collect::<Vec<_>>();
+    drop(boxes);
+
+    let drop_latency = slow.latencies().drop;
+    assert!(drop_latency.max >= Duration::from_millis(20));
+    assert_eq!(drop_latency.p999, drop_latency.max);
+    assert!(drop_latency.p50 < Duration::from_millis(20));
+}
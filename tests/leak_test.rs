@@ -0,0 +1,41 @@
+#![cfg(feature = "leak-tracking")]
+
+use lock_free_freelist::FreeList;
+use std::mem;
+
+#[test]
+fn counts_outstanding_handles() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    let boxes = (0..5).map(|i| free_list.alloc(i)).collect::<Vec<_>>();
+    assert_eq!(free_list.outstanding(), 5);
+    drop(boxes);
+    assert_eq!(free_list.outstanding(), 0);
+
+    let reused = free_list.reuse(9).unwrap();
+    assert_eq!(free_list.outstanding(), 1);
+    drop(reused);
+
+    assert_eq!(free_list.outstanding(), 0);
+    assert_eq!(free_list.leak_report(), None);
+}
+
+#[test]
+fn reports_forgotten_handles() {
+    let free_list = FreeList::<Box<u32>>::new().with_label("leaky");
+
+    mem::forget(free_list.alloc(1));
+    mem::forget(free_list.alloc(2));
+    drop(free_list.alloc(3));
+
+    assert_eq!(free_list.outstanding(), 2);
+
+    let report = free_list.leak_report().expect("leak should be reported");
+    assert!(report.starts_with("FreeList<u32> \"leaky\" has 2 outstanding Reuse handle(s)"));
+
+    #[cfg(feature = "leak-backtraces")]
+    {
+        assert_eq!(report.matches("created at:").count(), 2);
+        assert!(report.contains("reports_forgotten_handles"));
+    }
+}
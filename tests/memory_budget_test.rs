@@ -0,0 +1,84 @@
+use lock_free_freelist::{FreeList, MemoryBudget};
+use std::{sync::Arc, thread};
+
+#[test]
+fn budget_is_shared_and_refunded() {
+    let budget = MemoryBudget::new(10 * 8);
+
+    let first = FreeList::<Box<u64>>::new().with_memory_budget(budget.clone());
+    let second = FreeList::<Box<u64>>::new().with_memory_budget(budget.clone());
+
+    let allocated = (0..8)
+        .map(|i| first.alloc(i))
+        .chain((0..8).map(|i| second.alloc(i)))
+        .collect::<Vec<_>>();
+    drop(allocated);
+
+    // only 10 of the 16 fit in the budget
+    assert_eq!(first.len() + second.len(), 10);
+    assert_eq!(budget.used(), 80);
+
+    let reused = first.reuse(0).unwrap();
+    assert_eq!(budget.used(), 72);
+
+    drop(reused);
+    assert_eq!(budget.used(), 80);
+
+    first.shrink();
+    second.shrink();
+    assert_eq!(budget.used(), 0);
+}
+
+#[test]
+fn sized_budget_uses_capacity() {
+    let budget = MemoryBudget::new(1000);
+    let free_list = FreeList::<Box<Vec<u8>>>::new()
+        .with_memory_budget_sized(budget.clone(), |vec| vec.capacity());
+
+    drop(free_list.alloc(Vec::with_capacity(600)));
+    drop(free_list.alloc(Vec::with_capacity(600)));
+    assert_eq!(free_list.len(), 1);
+    assert_eq!(budget.used(), 600);
+
+    budget.set_limit(2000);
+    assert_eq!(free_list.prefill(10, || Vec::with_capacity(600)), 2);
+    assert_eq!(budget.used(), 1800);
+
+    drop(free_list);
+    assert_eq!(budget.used(), 0);
+}
+
+#[test]
+fn budget_holds_under_contention() {
+    let budget = MemoryBudget::new(20 * 4);
+    let free_lists = (0..4)
+        .map(|_| Arc::new(FreeList::<Box<u32>>::new().with_memory_budget(budget.clone())))
+        .collect::<Vec<_>>();
+
+    let threads = free_lists
+        .iter()
+        .map(|free_list| {
+            let free_list = Arc::clone(free_list);
+            let budget = budget.clone();
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    let objects = (0..16)
+                        .map(|_| free_list.reuse_or_alloc(i))
+                        .collect::<Vec<_>>();
+                    drop(objects);
+                    assert!(budget.used() <= budget.limit());
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    let retained = free_lists
+        .iter()
+        .map(|free_list| free_list.len())
+        .sum::<usize>();
+    assert_eq!(budget.used(), retained * 4);
+}
@@ -5,6 +5,7 @@ use std::thread;
 #[macro_use]
 extern crate lazy_static;
 
+#[allow(dead_code)]
 #[derive(Debug, Reusable)]
 struct Container {
     a: i32,
@@ -0,0 +1,64 @@
+use lock_free_freelist::{FreeList, OverflowPolicy};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn spill_keeps_everything() {
+    let free_list = FreeList::<Box<u64>>::new().with_overflow_policy(OverflowPolicy::Spill);
+
+    let boxes = (0..CAPACITY * 2)
+        .map(|i| free_list.alloc(i as u64))
+        .collect::<Vec<_>>();
+    drop(boxes);
+
+    let reused = (0..CAPACITY * 2)
+        .map(|i| {
+            free_list
+                .reuse(i as u64)
+                .expect("spilled pointer should be reused")
+        })
+        .collect::<Vec<_>>();
+
+    assert!(free_list.reuse(0).is_err());
+    drop(reused);
+}
+
+#[test]
+fn forward_to_parent() {
+    let parent = Arc::new(FreeList::<Box<u64>>::new());
+    let free_list = FreeList::<Box<u64>>::new()
+        .with_overflow_policy(OverflowPolicy::Forward(Arc::clone(&parent)));
+
+    let boxes = (0..CAPACITY + 3)
+        .map(|i| free_list.alloc(i as u64))
+        .collect::<Vec<_>>();
+    drop(boxes);
+
+    let reused = (0..3).map(|i| parent.reuse(i).unwrap()).collect::<Vec<_>>();
+
+    assert!(parent.reuse(0).is_err());
+    drop(reused);
+}
+
+#[test]
+fn callback_receives_overflow() {
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&received);
+
+    let free_list = FreeList::<Box<u64>>::new().with_overflow_policy(OverflowPolicy::Callback(
+        Box::new(move |_boxed| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }),
+    ));
+
+    let boxes = (0..CAPACITY + 5)
+        .map(|i| free_list.alloc(i as u64))
+        .collect::<Vec<_>>();
+    drop(boxes);
+
+    assert_eq!(received.load(Ordering::Relaxed), 5);
+}
@@ -0,0 +1,153 @@
+use lock_free_freelist::{Pool, Reusable};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(Reusable)]
+struct Parser {
+    buffer: Vec<u8>,
+    state: u32,
+}
+
+#[test]
+fn factory_only_called_when_empty() {
+    let created = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&created);
+
+    let pool = Pool::<Box<Parser>>::new(move || {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Parser {
+            buffer: Vec::with_capacity(1024),
+            state: 0,
+        }
+    })
+    .with_reset(|parser| {
+        parser.buffer.clear();
+        parser.state = 0;
+    });
+
+    for i in 0..10 {
+        let mut parser = pool.get();
+        assert_eq!(parser.state, 0);
+        assert!(parser.buffer.is_empty());
+
+        parser.state = i;
+        parser.buffer.push(1);
+    }
+
+    assert_eq!(created.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn multi_threaded_get() {
+    let pool = Arc::new(Pool::<Box<Vec<u32>>>::new(Vec::new).with_reset(|vec| vec.clear()));
+
+    let threads = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    let mut vec = pool.get();
+                    assert!(vec.is_empty());
+                    vec.push(i);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn bounded_pool_blocks_until_returned() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0).with_limit(2));
+
+    let first = pool.try_get().unwrap();
+    let second = pool.try_get().unwrap();
+    assert!(pool.try_get().is_none());
+    assert!(pool.get_blocking(Duration::from_millis(10)).is_none());
+    assert_eq!(pool.live(), 2);
+
+    let waiter = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            let third = pool.get_blocking(Duration::from_secs(10));
+            assert!(third.is_some());
+        })
+    };
+
+    thread::sleep(Duration::from_millis(20));
+    drop(first);
+    waiter.join().unwrap();
+
+    drop(second);
+    assert_eq!(pool.live(), 2);
+}
+
+#[test]
+fn raising_limit_wakes_waiters() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0).with_limit(0));
+
+    let waiter = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            let _ = pool.get();
+        })
+    };
+
+    thread::sleep(Duration::from_millis(20));
+    pool.set_limit(1);
+    waiter.join().unwrap();
+
+    assert_eq!(pool.live(), 1);
+}
+
+#[test]
+fn bounded_pool_never_exceeds_limit() {
+    let limit = 3;
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0).with_limit(limit));
+    let in_use = Arc::new(AtomicUsize::new(0));
+
+    let threads = (0..8)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            let in_use = Arc::clone(&in_use);
+            thread::spawn(move || {
+                for _ in 0..200 {
+                    let _object = pool.get();
+                    assert!(in_use.fetch_add(1, Ordering::SeqCst) < limit);
+                    in_use.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    assert!(pool.live() <= limit);
+}
+
+#[test]
+fn blocking_without_timeout() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0).with_limit(1));
+
+    let first = pool.get();
+
+    let waiter = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || pool.get_blocking(Duration::MAX).is_some())
+    };
+
+    thread::sleep(Duration::from_millis(20));
+    drop(first);
+    assert!(waiter.join().unwrap());
+}
@@ -0,0 +1,90 @@
+use lock_free_freelist::FreeList;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+#[test]
+fn released_in_fifo_order() {
+    let free_list = FreeList::<Box<u64>>::new().with_quarantine(2);
+
+    let boxes = (0..3).map(|i| free_list.alloc(i)).collect::<Vec<_>>();
+    let addresses = boxes
+        .iter()
+        .map(|reused| &***reused as *const u64)
+        .collect::<Vec<_>>();
+
+    let mut boxes = boxes.into_iter();
+
+    drop(boxes.next());
+    drop(boxes.next());
+    assert!(free_list.reuse(9).is_err());
+    assert_eq!(free_list.len(), 0);
+
+    drop(boxes.next());
+    assert_eq!(free_list.len(), 1);
+
+    let reused = free_list.reuse(9).unwrap();
+    assert_eq!(&**reused as *const u64, addresses[0]);
+}
+
+#[test]
+fn quarantined_contents_are_dropped_after_poison() {
+    let tracked = Arc::new(());
+    let poisoned = Arc::new(AtomicUsize::new(0));
+    let poison_count = poisoned.clone();
+
+    let free_list = FreeList::<Box<Option<Arc<()>>>>::new()
+        .with_quarantine(1)
+        .with_poison(move |contents| {
+            assert!(contents.is_some());
+            poison_count.fetch_add(1, Ordering::Relaxed);
+        });
+
+    drop(free_list.alloc(Some(tracked.clone())));
+
+    assert_eq!(poisoned.load(Ordering::Relaxed), 1);
+    assert_eq!(Arc::strong_count(&tracked), 1);
+}
+
+#[test]
+fn released_pointers_get_default_contents() {
+    let free_list = FreeList::<Box<u64>>::new()
+        .with_quarantine(1)
+        .with_poison(|contents| *contents = 0xdead);
+
+    let returned = free_list.alloc(5);
+    let address = &**returned as *const u64 as usize;
+    drop(returned);
+
+    // releases the first pointer from quarantine
+    drop(free_list.alloc(6));
+
+    let released = free_list.drain().next().unwrap();
+    assert_eq!(&*released as *const u64 as usize, address);
+    assert_eq!(*released, 0);
+}
+
+#[test]
+fn huge_quarantine() {
+    let free_list = FreeList::<Box<u64>>::new().with_quarantine(usize::MAX);
+
+    drop(free_list.alloc(1));
+    assert!(free_list.reuse(2).is_err());
+}
+
+#[test]
+fn quarantined_pointers_are_dropped() {
+    let free_list = FreeList::<Box<Vec<u8>>>::new().with_quarantine(8);
+
+    let boxes = (0..5)
+        .map(|_| free_list.alloc(vec![0; 16]))
+        .collect::<Vec<_>>();
+    drop(boxes);
+
+    assert_eq!(free_list.len(), 0);
+    unsafe {
+        free_list.clear();
+    }
+    assert_eq!(free_list.drain().count(), 0);
+}
@@ -0,0 +1,75 @@
+use lock_free_freelist::RawBlockPool;
+use std::{alloc::Layout, collections::HashSet, ptr::NonNull, thread};
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn blocks_are_reused() {
+    let layout = Layout::from_size_align(48, 64).unwrap();
+    let pool = RawBlockPool::new(layout);
+
+    let blocks = (0..10).map(|_| pool.allocate()).collect::<Vec<_>>();
+    assert!(blocks.iter().all(|block| block.as_ptr().align_offset(64) == 0));
+
+    let addresses = blocks.iter().copied().collect::<HashSet<_>>();
+    assert_eq!(addresses.len(), 10);
+
+    for block in blocks {
+        unsafe { pool.deallocate(block) };
+    }
+    assert_eq!(pool.len(), 10);
+
+    let reused = (0..10).map(|_| pool.allocate()).collect::<HashSet<_>>();
+    assert_eq!(reused, addresses);
+    assert!(pool.is_empty());
+
+    for block in reused {
+        unsafe { pool.deallocate(block) };
+    }
+}
+
+#[test]
+fn extra_blocks_are_freed() {
+    let pool = RawBlockPool::new(Layout::new::<[u64; 4]>());
+
+    let blocks = (0..CAPACITY + 10)
+        .map(|_| pool.allocate())
+        .collect::<Vec<_>>();
+    for block in blocks {
+        unsafe { pool.deallocate(block) };
+    }
+
+    assert_eq!(pool.len(), CAPACITY);
+}
+
+#[test]
+fn zero_sized_blocks() {
+    let pool = RawBlockPool::new(Layout::from_size_align(0, 8).unwrap());
+
+    let block = pool.allocate();
+    assert_eq!(block, NonNull::<u64>::dangling().cast());
+
+    unsafe { pool.deallocate(block) };
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn shared_between_threads() {
+    let pool = RawBlockPool::new(Layout::new::<u128>());
+
+    thread::scope(|scope| {
+        for t in 0..8u128 {
+            let pool = &pool;
+            scope.spawn(move || {
+                for i in 0..1000 {
+                    let block = pool.allocate().cast::<u128>();
+                    unsafe {
+                        block.as_ptr().write(t * 1000 + i);
+                        assert_eq!(block.as_ptr().read(), t * 1000 + i);
+                        pool.deallocate(block.cast());
+                    }
+                }
+            });
+        }
+    });
+}
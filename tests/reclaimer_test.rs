@@ -0,0 +1,161 @@
+use lock_free_freelist::{FreeList, Reclaimer, Reusable};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread::{self, ThreadId},
+    time::Duration,
+};
+
+#[derive(Reusable)]
+struct RecordDrop {
+    dropped_on: Arc<Mutex<Vec<ThreadId>>>,
+}
+
+impl Drop for RecordDrop {
+    fn drop(&mut self) {
+        self.dropped_on.lock().unwrap().push(thread::current().id());
+    }
+}
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn overflow_and_old_contents_dropped_on_reclaimer() {
+    let dropped_on = Arc::new(Mutex::new(Vec::new()));
+    let new = || RecordDrop {
+        dropped_on: Arc::clone(&dropped_on),
+    };
+
+    let reclaimer = Reclaimer::new();
+    let free_list = FreeList::<Box<RecordDrop>>::new().with_reclaimer(reclaimer.clone());
+
+    // CAPACITY go into the free list, 2 overflow
+    let allocated = (0..CAPACITY + 2)
+        .map(|_| free_list.alloc(new()))
+        .collect::<Vec<_>>();
+    drop(allocated);
+
+    // replaces the old contents of 3 pointers
+    let reused = (0..3)
+        .map(|_| free_list.reuse(new()).ok().unwrap())
+        .collect::<Vec<_>>();
+
+    reclaimer.flush();
+
+    let dropped_on = dropped_on.lock().unwrap();
+    assert_eq!(dropped_on.len(), 5);
+    assert!(dropped_on.iter().all(|id| *id != thread::current().id()));
+
+    drop(dropped_on);
+    drop(reused);
+}
+
+#[test]
+fn last_handle_drops_everything() {
+    let shared = Arc::new(());
+    let reclaimer = Reclaimer::new();
+    let clone = reclaimer.clone();
+
+    let threads = (0..4)
+        .map(|_| {
+            let reclaimer = reclaimer.clone();
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    reclaimer.retire(Arc::clone(&shared));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    drop(reclaimer);
+    drop(clone);
+
+    assert_eq!(Arc::strong_count(&shared), 1);
+}
+
+#[test]
+fn flush_while_others_retire() {
+    let reclaimer = Reclaimer::new();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let retirer = {
+        let reclaimer = reclaimer.clone();
+        let done = Arc::clone(&done);
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                reclaimer.retire(vec![0u8; 64]);
+            }
+        })
+    };
+
+    let shared = Arc::new(());
+
+    for _ in 0..100 {
+        reclaimer.retire(Arc::clone(&shared));
+        reclaimer.flush();
+        assert_eq!(Arc::strong_count(&shared), 1);
+    }
+
+    done.store(true, Ordering::Relaxed);
+    retirer.join().unwrap();
+}
+
+struct PanicOnDrop;
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("dropped");
+    }
+}
+
+#[test]
+fn panicking_destructor_does_not_stop_reclaimer() {
+    let reclaimer = Reclaimer::new();
+    let shared = Arc::new(());
+
+    reclaimer.retire(PanicOnDrop);
+    reclaimer.retire(1u8);
+    reclaimer.flush();
+
+    reclaimer.retire(Arc::clone(&shared));
+    reclaimer.flush();
+    assert_eq!(Arc::strong_count(&shared), 1);
+}
+
+struct DropLater {
+    reclaimer: Option<Reclaimer>,
+    go: mpsc::Receiver<()>,
+    done: mpsc::Sender<()>,
+}
+
+impl Drop for DropLater {
+    fn drop(&mut self) {
+        self.go.recv().unwrap();
+        drop(self.reclaimer.take());
+        self.done.send(()).unwrap();
+    }
+}
+
+#[test]
+fn last_handle_dropped_on_reclaimer_thread() {
+    let reclaimer = Reclaimer::new();
+    let (go_tx, go) = mpsc::channel();
+    let (done, done_rx) = mpsc::channel();
+
+    reclaimer.retire(DropLater {
+        reclaimer: Some(reclaimer.clone()),
+        go,
+        done,
+    });
+    drop(reclaimer);
+
+    go_tx.send(()).unwrap();
+    done_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "registry")]
+
+use lock_free_freelist::{registry, FreeList};
+
+fn find(label: &str) -> Option<registry::PoolSnapshot> {
+    registry::snapshot()
+        .into_iter()
+        .find(|pool| pool.label.as_deref() == Some(label))
+}
+
+#[test]
+fn registers_and_unregisters() {
+    let free_list = FreeList::<Box<u64>>::new().with_label("registers_and_unregisters");
+
+    let boxes = (0..3).map(|i| free_list.alloc(i)).collect::<Vec<_>>();
+    drop(boxes);
+    drop(free_list.reuse(7));
+
+    let pool = find("registers_and_unregisters").expect("free list should be registered");
+    assert_eq!(pool.type_name, "u64");
+    assert_eq!(pool.len, 3);
+    assert_eq!(pool.capacity, std::mem::size_of::<usize>() * 8);
+    assert_eq!(pool.stats.allocs, 3);
+    assert_eq!(pool.stats.reuses, 1);
+
+    drop(free_list);
+
+    assert!(find("registers_and_unregisters").is_none());
+}
+
+#[test]
+fn follows_moved_free_list() {
+    let free_lists = (0..4)
+        .map(|_| FreeList::<Box<u32>>::new())
+        .collect::<Vec<_>>();
+    let moved = free_lists
+        .into_iter()
+        .map(|free_list| free_list.with_label("follows_moved_free_list"))
+        .collect::<Vec<_>>();
+
+    drop(moved[0].alloc(1));
+
+    let pools = registry::snapshot()
+        .into_iter()
+        .filter(|pool| pool.label.as_deref() == Some("follows_moved_free_list"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(pools.len(), 4);
+    assert_eq!(pools.iter().map(|pool| pool.len).sum::<usize>(), 1);
+}
@@ -0,0 +1,42 @@
+use lock_free_freelist::{FreeList, Replenisher};
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn prefill_stops_when_full() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    assert_eq!(free_list.prefill(10, || 0), 10);
+    assert_eq!(free_list.prefill(CAPACITY, || 0), CAPACITY - 10);
+    assert_eq!(free_list.prefill(1, || 0), 0);
+}
+
+#[test]
+fn replenisher_refills_after_burst() {
+    let free_list = Arc::new(FreeList::<Box<u32>>::new());
+    let _replenisher =
+        Replenisher::spawn(Arc::clone(&free_list), 8, Duration::from_millis(1), || 7);
+
+    // Bursts are held on to so that only the replenisher can refill the free list
+    let mut reused = Vec::new();
+
+    for burst in 1..=3 {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while reused.len() < burst * 8 {
+            assert!(Instant::now() < deadline, "free list was not replenished");
+
+            match free_list.reuse(1) {
+                Ok(reuse) => reused.push(reuse),
+                Err(_) => thread::sleep(Duration::from_millis(1)),
+            }
+        }
+    }
+
+    assert!(reused.iter().all(|reuse| ***reuse == 1));
+}
@@ -0,0 +1,28 @@
+use lock_free_freelist::{FreeList, Pool};
+
+#[test]
+fn rejected_pointers_are_not_kept() {
+    let free_list = FreeList::<Box<String>>::new()
+        .with_return_filter(|string| string.capacity() <= 64 && !string.contains('\0'));
+
+    drop(free_list.alloc("x".repeat(1000)));
+    drop(free_list.alloc("in\0valid".to_string()));
+    assert_eq!(free_list.len(), 0);
+
+    drop(free_list.alloc("ok".to_string()));
+    assert_eq!(free_list.len(), 1);
+}
+
+#[test]
+fn rejections_free_pool_slots() {
+    let pool = Pool::<Box<Vec<u8>>>::new(Vec::new)
+        .with_free_list(FreeList::new().with_return_filter(|buf: &Vec<u8>| buf.len() < 10))
+        .with_limit(1);
+
+    let mut buf = pool.get();
+    buf.resize(100, 0);
+    drop(buf);
+
+    assert_eq!(pool.live(), 0);
+    assert!(pool.try_get().is_some());
+}
@@ -24,3 +24,28 @@ fn single_thread_test() {
         panic!();
     };
 }
+
+#[test]
+fn len_and_capacity() {
+    let free_list = FreeList::<Box<u32>>::new();
+    assert!(free_list.is_empty());
+    assert!(!free_list.is_full());
+
+    let allocated = (0..free_list.capacity())
+        .map(|i| free_list.alloc(i as u32))
+        .collect::<Vec<_>>();
+    assert!(free_list.is_empty());
+
+    drop(allocated);
+    assert_eq!(free_list.len(), free_list.capacity());
+    assert!(free_list.is_full());
+
+    let reused = free_list.reuse(0).unwrap();
+    assert_eq!(free_list.len(), free_list.capacity() - 1);
+
+    let debug = format!("{:?}", free_list);
+    assert!(debug.contains(&format!("len: {}", free_list.capacity() - 1)));
+    assert!(debug.contains("reader_bitmap: 0b"));
+
+    drop(reused);
+}
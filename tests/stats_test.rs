@@ -0,0 +1,83 @@
+#![cfg(feature = "stats")]
+
+use lock_free_freelist::{FreeList, OverflowPolicy, Pool};
+use std::{sync::Arc, thread};
+
+const CAPACITY: usize = std::mem::size_of::<usize>() * 8;
+
+#[test]
+fn counts_reuses_allocs_and_throws() {
+    let free_list = FreeList::<Box<u32>>::new();
+
+    let allocated = (0..10)
+        .map(|i| free_list.reuse_or_alloc(i))
+        .collect::<Vec<_>>();
+    drop(allocated);
+
+    let reused = (0..4)
+        .map(|i| free_list.reuse_or_alloc(i))
+        .collect::<Vec<_>>();
+
+    let stats = free_list.stats();
+    assert_eq!(stats.allocs, 10);
+    assert_eq!(stats.reuses, 4);
+    assert_eq!(stats.throws, 10);
+    assert_eq!(stats.overflows, 0);
+    assert_eq!(stats.len, 6);
+    assert_eq!(stats.peak_len, 10);
+
+    drop(reused);
+}
+
+#[test]
+fn counts_overflows() {
+    let free_list = FreeList::<Box<u32>>::new().with_overflow_policy(OverflowPolicy::Spill);
+
+    let allocated = (0..CAPACITY + 3)
+        .map(|_| free_list.alloc(0))
+        .collect::<Vec<_>>();
+    drop(allocated);
+
+    let stats = free_list.stats();
+    assert_eq!(stats.overflows, 3);
+    assert_eq!(stats.len, CAPACITY);
+    assert_eq!(stats.peak_len, CAPACITY);
+}
+
+#[test]
+fn counts_across_threads() {
+    let pool = Arc::new(Pool::<Box<u32>>::new(|| 0));
+
+    let threads = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    let _object = pool.get();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    let stats = pool.free_list().stats();
+    assert_eq!(stats.reuses + stats.allocs, 4000);
+    assert_eq!(stats.throws, 4000);
+    assert_eq!(stats.allocs as usize, stats.len);
+}
+
+#[test]
+fn counts_rejections() {
+    let free_list = FreeList::<Box<u32>>::new().with_return_filter(|x| *x % 2 == 0);
+
+    let allocated = (0..10).map(|i| free_list.alloc(i)).collect::<Vec<_>>();
+    drop(allocated);
+
+    let stats = free_list.stats();
+    assert_eq!(stats.rejected, 5);
+    assert_eq!(stats.throws, 5);
+    assert_eq!(stats.len, 5);
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "tracing")]
+
+use lock_free_freelist::FreeList;
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Records every event as its message followed by its fields.
+#[derive(Clone, Default)]
+struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{:?}", value));
+        } else {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(String::new());
+        event.record(&mut fields);
+        self.events.lock().unwrap().push(fields.0);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn events_carry_label_and_type() {
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let free_list = FreeList::<Box<u32>>::new().with_label("traced");
+
+        assert!(free_list.reuse(1).is_err());
+        free_list.prefill(2, || 0);
+        drop(free_list.reuse(1));
+        free_list.trim_to(0);
+    });
+
+    let events = recorder.events.lock().unwrap();
+
+    assert_eq!(
+        *events,
+        vec![
+            "reuse miss label=\"traced\" type_name=\"u32\"",
+            "prefill label=\"traced\" type_name=\"u32\" requested=2 added=2",
+            "reuse hit label=\"traced\" type_name=\"u32\"",
+            "trim label=\"traced\" type_name=\"u32\" len=0 trimmed=2",
+            "clear label=\"traced\" type_name=\"u32\" len=0",
+        ]
+    );
+}
+
+#[test]
+fn overflow_drops_are_traced() {
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let free_list = FreeList::<Box<u32>>::new();
+
+        let boxes = (0..std::mem::size_of::<usize>() * 8 + 2)
+            .map(|i| free_list.alloc(i as u32))
+            .collect::<Vec<_>>();
+        drop(boxes);
+    });
+
+    let events = recorder.events.lock().unwrap();
+    let dropped = events
+        .iter()
+        .filter(|event| event.starts_with("free list full, dropping pointer label=\"\""))
+        .count();
+
+    assert_eq!(dropped, 2);
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "zeroize")]
+
+use lock_free_freelist::FreeList;
+
+#[test]
+fn wiped_on_return() {
+    let free_list = FreeList::<Box<Vec<u8>>>::new().with_zeroize();
+
+    let mut key = free_list.alloc(Vec::with_capacity(16));
+    key.extend_from_slice(&[0xab; 16]);
+    let bytes = key.as_ptr();
+    drop(key);
+
+    // The buffer is kept, with its capacity, by the pointer in the free list
+    let wiped = unsafe { std::slice::from_raw_parts(bytes, 16) };
+    assert_eq!(wiped, &[0; 16]);
+
+    let reused = free_list.reuse(Vec::new()).unwrap();
+    assert!(reused.is_empty());
+}
+
+#[test]
+fn only_wiped_when_asked() {
+    let plain = FreeList::<Box<u64>>::new();
+    let wiping = FreeList::<Box<u64>>::new().with_zeroize();
+
+    let token = plain.alloc(0xabcd);
+    let stale = &**token as *const u64;
+    drop(token);
+    assert_eq!(unsafe { *stale }, 0xabcd);
+
+    let token = wiping.alloc(0xabcd);
+    let stale = &**token as *const u64;
+    drop(token);
+    assert_eq!(unsafe { *stale }, 0);
+}